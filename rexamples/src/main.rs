@@ -1,5 +1,5 @@
 use rings::{
-    app::{AppBuilder, AppBuilderWebReconfigor},
+    app::{AppBuilder, AppBuilderWebReconfigor, Launch},
     axum,
     rings::R,
     tokio,
@@ -8,7 +8,17 @@ use web::{action::api::api_actions, middleware::api::signator::use_signator};
 
 #[tokio::main]
 async fn main() {
-    let mut builder = AppBuilder::new("rexamples").await;
+    let mut builder = match AppBuilder::with_args("rexamples").await {
+        Ok(Launch::Run(builder)) => builder,
+        Ok(Launch::PrintConfig(printed)) => {
+            println!("{}", printed);
+            return;
+        },
+        Err(reason) => {
+            eprintln!("{}", reason);
+            std::process::exit(2);
+        },
+    };
     builder.use_model().await;
 
     rings::hey_service!(service);
@@ -36,9 +36,44 @@ pub(crate) fn define_normals(input: TokenStream) -> TokenStream {
 
     let expanded = quote! {
 
-        #[doc = "Finder struct for handling data queries."]
+        #[doc = "Finder struct for handling data queries, reads go to a replica of the default database when any."]
         pub struct #retrieve;
 
+        impl #retrieve {
+            #[doc = "reader of the default database, a replica when any, see `rings::model::reader`"]
+            pub fn reader() -> rings::erx::ResultBoxedE<rings::model::router::Readonly> {
+                rings::model::reader()
+            }
+
+            #[doc = "every row"]
+            pub async fn read_all() -> rings::erx::ResultBoxedE<Vec<#model_alias>> {
+                Self::read(<#entity_alias as rings::sea_orm::EntityTrait>::find()).await
+            }
+
+            #[doc = "the row of the primary key, `None` when missing"]
+            pub async fn read_by_id<K>(id: K) -> rings::erx::ResultBoxedE<Option<#model_alias>>
+            where
+                K: Into<<<#entity_alias as rings::sea_orm::EntityTrait>::PrimaryKey as rings::sea_orm::PrimaryKeyTrait>::ValueType>,
+            {
+                Self::read_one(<#entity_alias as rings::sea_orm::EntityTrait>::find_by_id(id)).await
+            }
+
+            #[doc = "rows of a select, e.g. `read(Ent::find().filter(...))`"]
+            pub async fn read(select: rings::sea_orm::Select<#entity_alias>) -> rings::erx::ResultBoxedE<Vec<#model_alias>> {
+                select.all(&Self::reader()?).await.map_err(rings::model::router::db_erx)
+            }
+
+            #[doc = "the first row of a select, `None` when none"]
+            pub async fn read_one(select: rings::sea_orm::Select<#entity_alias>) -> rings::erx::ResultBoxedE<Option<#model_alias>> {
+                select.one(&Self::reader()?).await.map_err(rings::model::router::db_erx)
+            }
+
+            #[doc = "count of the rows of a select"]
+            pub async fn read_count(select: rings::sea_orm::Select<#entity_alias>) -> rings::erx::ResultBoxedE<u64> {
+                rings::sea_orm::PaginatorTrait::count(select, &Self::reader()?).await.map_err(rings::model::router::db_erx)
+            }
+        }
+
         #[doc = "Mutator struct for handling data persistence and CRUD operations."]
         pub struct #persist;
 
@@ -48,7 +48,7 @@ pub(crate) fn mark(attr: TokenStream, item: TokenStream) -> TokenStream {
             let _in_module = module_path!();
 
             ringm::service_resolve!(#func_name, #input_module);
-            rings::service::registe_to_shared::<#struct_ident>().await;
+            rings::service::registe_to_building::<#struct_ident>().await;
 
             // rings::prelude::tracing::info!("---{} {}, {:?}",#func_name, #struct_ident, _in_module);
             println!("Service registered with function: {}", #func_name);
@@ -122,7 +122,7 @@ pub(crate) fn expand(input: TokenStream) -> TokenStream {
             quote! {
                 {
                     #using_quote;
-                    rings::service::registe_to_shared::<#struct_ident>().await;
+                    rings::service::registe_to_building::<#struct_ident>().await;
                     println!("Service registered with directy: {}", #ident_name);
                     rings::prelude::tracing::info!("Service registered with directy: {}", #ident_name);
                 }
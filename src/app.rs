@@ -1,5 +1,5 @@
 use crate::{
-    rings::{RingsApplication, R},
+    rings::{moment_begin, Moment, RingsApplication, R},
     s,
     web::make_web,
 };
@@ -11,6 +11,14 @@ pub struct AppBuilder {
     rings_app: RingsApplication,
 }
 
+/// what `AppBuilder::with_args` made of the command line
+pub enum Launch {
+    /// run the app, built on the builder
+    Run(AppBuilder),
+    /// `--print-config` given, the effective config to print, nothing to run
+    PrintConfig(String),
+}
+
 // pub type AppBuilderWebReconfigor = (String,
 //     fn() -> Vec<axum::Router>,
 //     fn(web: &mut crate::web::Web) -> &mut crate::web::Web,
@@ -54,6 +62,46 @@ impl AppBuilder {
         AppBuilder { rings_app }
     }
 
+    /// new rings app builder, config arguments of the process applied first, see `conf::args`.
+    /// the binary decides what to do when there is nothing to run or the arguments are invalid.
+    ///
+    /// # Arguments
+    ///
+    /// * `defaults_name` - The name of the configuration file.
+    ///
+    /// # Returns
+    ///
+    /// * `Launch` - The rings app builder, or the effective config when `--print-config` given.
+    /// * `Err(reason)` - The arguments are invalid, or can not be applied: already installed, or config already read.
+    pub async fn with_args(defaults_name: &str) -> Result<Launch, String> {
+        let args = crate::conf::args::ConfigArgs::from_env()?;
+
+        let print_config = args.print_config;
+        crate::conf::args::install(args).map_err(|ex| ex.message_string())?;
+
+        if print_config {
+            let printed = crate::conf::args::print_config(&*crate::conf::settings().read().await, &crate::conf::config_layers());
+            return Ok(Launch::PrintConfig(printed));
+        }
+
+        Ok(Launch::Run(Self::new(defaults_name).await))
+    }
+
+    /// new rings app builder with an exact application name
+    /// use it when a process runs more than one rings application
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The rings application name, look it up later with `R::instance(name)`.
+    ///
+    /// # Returns
+    ///
+    /// * `AppBuilder` - The rings app builder.
+    pub async fn named(name: &str) -> Self {
+        let rings_app: RingsApplication = R::make(name).await;
+        AppBuilder { rings_app }
+    }
+
     /// use model
     ///
     pub async fn use_model(&mut self) -> &mut Self {
@@ -65,7 +113,14 @@ impl AppBuilder {
             },
             Some(backends) => {
                 let backends = backends.clone();
-                crate::model::initialize_model_connection(backends).await;
+                let models = self.rings_app.read().await.models();
+
+                let begin = moment_begin();
+                let moments = models.initialize(backends).await;
+
+                let mut app = self.rings_app.write().await;
+                app.add_moment(Moment::since("model connections", begin));
+                moments.into_iter().for_each(|m| app.add_moment(m));
             },
         }
         self
@@ -123,9 +178,10 @@ impl AppBuilder {
     ///
     /// * `AppBuilder` - The rings app builder.
     pub async fn use_scheduler(&mut self) -> &mut Self {
-        let scheduler_manager = crate::scheduler::SchedulerManager::new().await;
         let app = Arc::clone(&self.rings_app);
-        app.write().await.register_mod(scheduler_manager).await;
+        let mut app = app.write().await;
+        let scheduler_manager = crate::scheduler::SchedulerManager::new(app.services()).await;
+        app.register_mod(scheduler_manager).await;
         self
     }
 
@@ -1,3 +1,11 @@
+pub mod args;
+pub mod extends;
+pub mod reload;
+pub mod sample;
+pub mod secret;
+pub mod section;
+pub mod validate;
+
 use crate::core::runtime::tokio_block_on;
 use config::{Config, Value};
 use serde::{Deserialize, Serialize};
@@ -15,6 +23,10 @@ use std::str::FromStr;
 use std::sync::OnceLock;
 use tokio::sync::RwLock;
 
+pub use reload::{reload, subscribe, ConfigChange};
+pub use sample::sample;
+pub use section::section;
+
 //get or default
 pub struct GetDefault;
 pub struct GetOption;
@@ -36,9 +48,11 @@ pub fn rebit() -> &'static RwLock<Rebit> {
                     model: Model { backends: None },
                     log: None,
                     extends: None,
+                    shutdown: None,
                 }
             } else {
-                tokio_block_on(async { settings().read().await.clone().try_deserialize::<Rebit>() }).expect("rebit loading error")
+                let config = tokio_block_on(async { settings().read().await.clone() });
+                validate::load(&config).unwrap_or_else(|report| panic!("{}", report))
             }
         })
     })
@@ -51,38 +65,14 @@ pub fn rebit() -> &'static RwLock<Rebit> {
 /// # Returns
 /// * `&'static RwLock<Config>` - config instance
 pub fn settings() -> &'static RwLock<Config> {
-    static CONFIG: OnceLock<RwLock<Config>> = OnceLock::new();
     CONFIG.get_or_init(|| RwLock::new(init_config()))
 }
 
-/// get extends config with config file name
-/// # Arguments
-/// * `file` - config file name (full path please)
-/// # Returns
-/// * `&'static Config` - config instance
-/// # Panics
-/// * if file not found or invalid
-pub async fn extends(file: &str) -> &'static Config {
-    static EXTENDS: OnceLock<RwLock<std::collections::HashMap<&'static str, Config>>> = OnceLock::new();
-    let extends = EXTENDS.get_or_init(|| RwLock::new(std::collections::HashMap::new()));
-
-    let read = extends.read().await;
-    if read.contains_key(file) {
-        let c = read.get(file).unwrap();
-        return unsafe { &*(c as *const Config) };
-    }
-
-    let mut write = extends.write().await;
-    let c = {
-        let conf = config::File::with_name(file).required(true);
-        let builder = Config::builder().add_source(conf);
-        builder.build().unwrap()
-    };
-
-    let key = Box::leak(file.to_owned().into_boxed_str());
-    write.insert(key, c);
+static CONFIG: OnceLock<RwLock<Config>> = OnceLock::new();
 
-    unsafe { &*(extends.read().await.get(file).unwrap() as *const Config) }
+/// config already read, arguments installed from now on would be ignored
+pub(crate) fn config_read() -> bool {
+    CONFIG.get().is_some()
 }
 
 /// init config
@@ -90,39 +80,151 @@ pub async fn extends(file: &str) -> &'static Config {
 /// * `Config` - config instance
 // #[cfg(not(test))]
 fn init_config() -> Config {
+    build_config().unwrap()
+}
+
+/// run mode, `--mode` or `REBT_RUN_MODE`, `development` by default
+pub fn run_mode() -> String {
     //development production testing
-    let run_mode = std::env::var("REBT_RUN_MODE").unwrap_or("development".to_string());
+    args::installed()
+        .and_then(|a| a.mode.clone())
+        .or(std::env::var("REBT_RUN_MODE").ok())
+        .unwrap_or("development".to_string())
+}
 
-    tracing::info!("REBT_RUN_MODE={}", run_mode);
+/// config directory, `--config-dir` or `REBT_CONFIG_PATH`, `config` by default
+pub fn config_dir() -> String {
+    args::installed().and_then(|a| a.config_dir.clone()).or(std::env::var("REBT_CONFIG_PATH").ok()).unwrap_or("config".to_string())
+}
 
-    let config_path = std::env::var("REBT_CONFIG_PATH").unwrap_or("config".to_string());
+/// config files in loading order, later ones override earlier ones:
+/// `config.yml`, `{run_mode}.yml`, `local.yml` under `config_dir`
+pub fn config_files() -> Vec<String> {
+    let run_mode = run_mode();
+    let config_path = config_dir();
     // while !crate::tools::file::File(config_path.clone()).is_directory() {
     //     //TODO
     //     break;
     //     // let workdir = env::current_dir().unwrap().to_str().unwrap().to_string();
     // }
 
-    tracing::info!("Config file path: {}", config_path);
+    vec![format!("{config_path}/config.yml"), format!("{config_path}/{run_mode}.yml"), format!("{config_path}/local.yml")]
+}
+
+/// layer name of `REBT_*` environment values
+pub const ENV_LAYER: &str = "environment";
+
+/// Config Layer, one source of `build_config` on its own, telling where a value came from
+/// # Fields
+/// * `name` - config file path, `ENV_LAYER` or `args::SET_LAYER`
+/// * `config` - values of this source only
+pub struct ConfigLayer {
+    pub name: String,
+    config: Config,
+}
+
+impl ConfigLayer {
+    pub fn new(name: &str, config: Config) -> Self {
+        ConfigLayer { name: name.to_string(), config }
+    }
+
+    /// the layer sets `key`
+    pub fn defines(&self, key: &str) -> bool {
+        self.config.get::<Value>(key).is_ok()
+    }
+}
+
+/// name of the last layer setting `key`, `None` when no layer does, e.g. a default
+pub fn layer_of<'a>(layers: &'a [ConfigLayer], key: &str) -> Option<&'a str> {
+    layers.iter().rev().find(|layer| layer.defines(key)).map(|layer| layer.name.as_str())
+}
 
-    let conf = config::File::with_name(&format!("{config_path}/config.yml")).required(false);
-    let mode = config::File::with_name(&format!("{config_path}/{run_mode}.yml")).required(false);
-    let local = config::File::with_name(&format!("{config_path}/local.yml")).required(false);
+type LayerSource = (String, Box<dyn config::Source + Send + Sync>);
 
-    let mut builder = Config::builder().add_source(conf).add_source(mode).add_source(local);
+/// sources of `build_config` with their layer names, in loading order, later ones override earlier ones
+fn config_sources() -> Result<Vec<LayerSource>, config::ConfigError> {
+    let mut sources: Vec<LayerSource> = vec![];
+    for file in config_files() {
+        let source = config::File::with_name(&file).required(false);
+        sources.push((file, Box::new(source)));
+    }
     #[cfg(test)]
     {
         use crate::tools::tests::tools::project_dir;
 
         let tests_load = format!("{}/tests/using-test-config.yml", project_dir().to_string_lossy());
-        tracing::info!("test mode, loading: {}", tests_load);
-        println!("test mode, loading: {}", tests_load);
+        let source = config::File::with_name(tests_load.as_str()).required(false);
+        sources.push((tests_load, Box::new(source)));
+    }
 
-        builder = builder.add_source(config::File::with_name(tests_load.as_str()).required(false));
+    sources.push((ENV_LAYER.to_string(), Box::new(config::Environment::with_prefix("REBT"))));
+
+    let mut sets = Config::builder();
+    for (key, value) in args::installed().map(|a| a.sets.as_slice()).unwrap_or_default() {
+        sets = sets.set_override(key.as_str(), value.as_str())?;
     }
+    sources.push((args::SET_LAYER.to_string(), Box::new(sets.build()?)));
+
+    Ok(sources)
+}
 
-    builder = builder.add_source(config::Environment::with_prefix("REBT"));
+/// every layer of `build_config`, each source built on its own, see `layer_of`.
+/// sources failing to build are left out, `build_config` reports them
+pub fn config_layers() -> Vec<ConfigLayer> {
+    let sources = config_sources().unwrap_or_default();
+    sources.into_iter().filter_map(|(name, source)| Config::builder().add_source(vec![source]).build().ok().map(|config| ConfigLayer { name, config })).collect()
+}
+
+/// build config from config files, `REBT_*` environment and `--set` overrides
+/// # Returns
+/// * `Result<Config, ConfigError>` - config instance
+pub fn build_config() -> Result<Config, config::ConfigError> {
+    tracing::info!("REBT_RUN_MODE={}", run_mode());
+    tracing::info!("Config files: {:?}", config_files());
 
-    builder.build().unwrap()
+    let sources: Vec<Box<dyn config::Source + Send + Sync>> = config_sources()?.into_iter().map(|(_, source)| source).collect();
+    resolve_secrets(Config::builder().add_source(sources).build()?)
+}
+
+/// resolve secret references (`env:`, `file:`, `base64:`) of every string value, see `secret`
+/// values not in the reference syntax are kept, a doubled colon (`env::VAR`) escapes a literal
+fn resolve_secrets(config: Config) -> Result<Config, config::ConfigError> {
+    fn collect(path: &str, value: &serde_json::Value, into: &mut Vec<(String, String)>) -> Result<(), config::ConfigError> {
+        match value {
+            serde_json::Value::Object(map) => {
+                for (k, v) in map {
+                    let key = if path.is_empty() { k.clone() } else { format!("{}.{}", path, k) };
+                    collect(&key, v, into)?;
+                }
+            },
+            serde_json::Value::Array(items) => {
+                for (i, v) in items.iter().enumerate() {
+                    collect(&format!("{}[{}]", path, i), v, into)?;
+                }
+            },
+            serde_json::Value::String(s) => {
+                let resolved = secret::resolve(s).map_err(|ex| config::ConfigError::Message(format!("{}: secret {}", path, ex)))?;
+                if let Some(resolved) = resolved {
+                    into.push((path.to_string(), resolved));
+                }
+            },
+            _ => {},
+        }
+        Ok(())
+    }
+
+    let mut resolved = vec![];
+    collect("", &config.clone().try_deserialize::<serde_json::Value>()?, &mut resolved)?;
+    if resolved.is_empty() {
+        return Ok(config);
+    }
+
+    // the layer of a resolved value is still the one of its reference, see `layer_of`
+    let mut builder = Config::builder().add_source(config);
+    for (key, value) in resolved {
+        builder = builder.set_override(key, value)?;
+    }
+    builder.build()
 }
 
 // #[cfg(test)]
@@ -201,7 +303,8 @@ impl Has {
 /// * `model` - rebit model config
 /// * `log` - rebit log config
 /// * `extends` - rebit extends config
-#[derive(Debug, Deserialize, Serialize, Clone)]
+/// * `shutdown` - rebit shutdown config
+#[derive(Deserialize, Serialize, Clone)]
 pub struct Rebit {
     pub name: String,
     pub short: String,
@@ -210,6 +313,45 @@ pub struct Rebit {
     pub model: Model,
     pub log: Option<Log>,
     pub extends: Option<DictString>,
+    pub shutdown: Option<Shutdown>,
+}
+
+/// secrets redacted, see `secret::redact_json`
+impl fmt::Debug for Rebit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut dump = serde_json::to_value(self).map_err(|_| fmt::Error)?;
+        secret::redact_json(&mut dump);
+        write!(f, "Rebit {}", dump)
+    }
+}
+
+/// Rebit shutdown config
+/// # Fields
+/// * `deadline` - overall shutdown deadline in seconds
+/// * `mods` - per mod shutdown deadline in seconds, keyed by mod name
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[serde(default)]
+pub struct Shutdown {
+    pub deadline: u64,
+    pub mods: Option<Dict<u64>>,
+}
+
+impl Default for Shutdown {
+    fn default() -> Self {
+        Shutdown { deadline: 30, mods: None }
+    }
+}
+
+impl Shutdown {
+    /// overall shutdown deadline
+    pub fn deadline(&self) -> std::time::Duration {
+        std::time::Duration::from_secs(self.deadline)
+    }
+
+    /// shutdown deadline of the named mod, if configured
+    pub fn mod_deadline(&self, name: &str) -> Option<std::time::Duration> {
+        self.mods.as_ref()?.get(name).map(|secs| std::time::Duration::from_secs(*secs))
+    }
 }
 
 /// Rebit log config
@@ -254,10 +396,14 @@ pub struct Web {
 /// # Fields
 /// * `Redis` - redis backend
 /// * `Postgres` - postgres backend
+/// * `Sqlite` - sqlite backend, e.g. `sqlite://data/rings.db?mode=rwc`
+/// * `MySQL` - mysql backend
 #[derive(Debug, Deserialize, Serialize, PartialEq, Clone, Eq)]
 pub enum BackendKind {
     Redis,
     Postgres,
+    Sqlite,
+    MySQL,
 }
 
 impl Default for Log {
@@ -286,6 +432,24 @@ impl Default for Rebit {
             model: Model { backends: None },
             log: Default::default(),
             extends: Default::default(),
+            shutdown: Default::default(),
+        }
+    }
+}
+
+impl BackendKind {
+    /// backend is a database, not redis
+    pub fn is_database(&self) -> bool {
+        !matches!(self, BackendKind::Redis)
+    }
+
+    /// sql dialect of a database backend, for the json helpers of `model::jq`
+    pub fn rdbms(&self) -> Option<crate::model::dbms::RDBMS> {
+        match self {
+            BackendKind::Redis => None,
+            BackendKind::Postgres => Some(crate::model::dbms::RDBMS::Postgres),
+            BackendKind::Sqlite => Some(crate::model::dbms::RDBMS::SQLite),
+            BackendKind::MySQL => Some(crate::model::dbms::RDBMS::MySQL),
         }
     }
 }
@@ -302,6 +466,8 @@ impl FromStr for BackendKind {
         match s.to_lowercase().as_str() {
             "redis" => Ok(BackendKind::Redis),
             "postgres" => Ok(BackendKind::Postgres),
+            "sqlite" => Ok(BackendKind::Sqlite),
+            "mysql" => Ok(BackendKind::MySQL),
             _ => Err(format!("unknown backend kind: {}", s)),
         }
     }
@@ -312,18 +478,109 @@ impl fmt::Display for BackendKind {
         match self {
             BackendKind::Redis => write!(f, "Redis"),
             BackendKind::Postgres => write!(f, "Postgre"),
+            BackendKind::Sqlite => write!(f, "Sqlite"),
+            BackendKind::MySQL => write!(f, "MySQL"),
         }
     }
 }
 
-#[derive(Debug, Deserialize, Serialize, Clone)]
+/// Rebit model backend
+/// # Fields
+/// * `kind` - backend kind
+/// * `readonly` - backend is readonly
+/// * `connect` - connect string
+/// * `default` - the default one of the databases, or of the redis backends, see `Model::default_name`
+/// * `replica_of` - primary backend name when this one is a readonly replica of it, see `model::router`
+/// * `weight` - read weight of a replica, 1 by default, 0 takes no reads
+/// * `pool` - connection pool of a database backend, defaults apply to missing fields
+/// * `namespace` - key namespace of a redis backend, the rebit `short` name if missing, empty for none
+/// * `options` - backend options
+#[derive(Deserialize, Serialize, Clone)]
 pub struct Backend {
     pub kind: BackendKind,
     pub readonly: bool,
     pub connect: String,
+    #[serde(default)]
+    pub default: bool,
+    #[serde(default)]
+    pub replica_of: Option<String>,
+    #[serde(default)]
+    pub weight: Option<u8>,
+    #[serde(default)]
+    pub pool: Pool,
+    #[serde(default)]
+    pub namespace: Option<String>,
     pub options: Option<DictString>,
 }
 
+/// Rebit database backend pool
+/// # Fields
+/// * `max_connections` - max pooled connections, 100
+/// * `min_connections` - connections kept open, 2
+/// * `connect_timeout` - seconds to connect, 8
+/// * `acquire_timeout` - seconds to acquire a pooled connection, 8
+/// * `idle_timeout` - seconds an idle connection is kept, 300
+/// * `max_lifetime` - seconds a connection lives, 3600
+/// * `lazy` - connect on first use instead of at startup, false
+/// * `statement_log` - statement logging level: off, error, warn, info, debug or trace, `info`
+/// * `slow_threshold` - milliseconds a statement takes to be logged as slow at warn, 2000
+/// * `application_name` - application name reported to the database (postgres), the rebit name if missing
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+#[serde(default)]
+pub struct Pool {
+    pub max_connections: u32,
+    pub min_connections: u32,
+    pub connect_timeout: u64,
+    pub acquire_timeout: u64,
+    pub idle_timeout: u64,
+    pub max_lifetime: u64,
+    pub lazy: bool,
+    pub statement_log: String,
+    pub slow_threshold: u64,
+    pub application_name: Option<String>,
+}
+
+impl Default for Pool {
+    fn default() -> Self {
+        Pool {
+            max_connections: 100,
+            min_connections: 2,
+            connect_timeout: 8,
+            acquire_timeout: 8,
+            idle_timeout: 60 * 5,
+            max_lifetime: 60 * 60,
+            lazy: false,
+            statement_log: "info".to_string(),
+            slow_threshold: 2_000,
+            application_name: None,
+        }
+    }
+}
+
+impl Pool {
+    /// statement logging level, `None` when unknown
+    pub fn statement_log_level(&self) -> Option<log::LevelFilter> {
+        log::LevelFilter::from_str(self.statement_log.trim()).ok()
+    }
+}
+
+/// connect string redacted
+impl fmt::Debug for Backend {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Backend")
+            .field("kind", &self.kind)
+            .field("readonly", &self.readonly)
+            .field("connect", &secret::REDACTED)
+            .field("default", &self.default)
+            .field("replica_of", &self.replica_of)
+            .field("weight", &self.weight)
+            .field("pool", &self.pool)
+            .field("namespace", &self.namespace)
+            .field("options", &self.options.as_ref().map(|o| o.keys().collect::<Vec<_>>()))
+            .finish()
+    }
+}
+
 #[derive(Debug, Deserialize, Serialize, Clone)]
 pub struct Model {
     pub backends: Option<Dict<Backend>>,
@@ -336,6 +593,27 @@ impl Model {
             Some(backends) => backends.get(name).cloned(),
         }
     }
+
+    /// replicas of a primary backend, sorted by name
+    pub fn replicas_of(&self, primary: &str) -> Vec<(String, Backend)> {
+        let mut replicas: Vec<(String, Backend)> =
+            self.backends.iter().flatten().filter(|(_, b)| b.replica_of.as_deref() == Some(primary)).map(|(n, b)| (n.clone(), b.clone())).collect();
+        replicas.sort_by(|a, b| a.0.cmp(&b.0));
+        replicas
+    }
+
+    /// name of the default database backend, or of the default redis backend:
+    /// the one marked `default`, else the one named `default`, else the first by name. replicas never are
+    /// # Arguments
+    /// * `database` - true for databases, false for redis
+    pub fn default_name(&self, database: bool) -> Option<String> {
+        let mut names: Vec<(&String, &Backend)> =
+            self.backends.iter().flatten().filter(|(_, b)| b.kind.is_database() == database && b.replica_of.is_none()).collect();
+        names.sort_by_key(|(name, _)| *name);
+
+        let marked = names.iter().find(|(_, b)| b.default).or(names.iter().find(|(name, _)| name.as_str() == "default"));
+        marked.or(names.first()).map(|(name, _)| (*name).clone())
+    }
 }
 
 impl Rebit {
@@ -410,11 +688,48 @@ mod tests {
         println!("{:?}", GetOption::get::<Vec<Port>>("providers.cnpc.management.ports").await);
     }
 
+    #[test]
+    fn test_resolve_secrets() {
+        let yaml = "name: logos\nlist:\n  - plain\n  - base64:c2Vjb25k\nmodel:\n  backends:\n    pg:\n      connect: base64:cG9zdGdyZXM6Ly9hOmJAYy9k\n";
+        let config = Config::builder().add_source(config::File::from_str(yaml, config::FileFormat::Yaml)).build().unwrap();
+
+        let resolved = resolve_secrets(config).unwrap();
+        assert_eq!(resolved.get_string("model.backends.pg.connect").unwrap(), "postgres://a:b@c/d");
+        assert_eq!(resolved.get_string("list[1]").unwrap(), "second");
+        assert_eq!(resolved.get_string("name").unwrap(), "logos");
+        assert!(secret::is_secret("postgres://a:b@c/d"));
+
+        let broken = Config::builder().set_override("token", "env:REBT_SECRET_NOT_EXISTS_FOR_TEST").unwrap().build().unwrap();
+        assert!(resolve_secrets(broken).unwrap_err().to_string().contains("token: secret env"));
+
+        let literal = Config::builder().set_override("note", "env::REBT_SECRET_NOT_EXISTS_FOR_TEST").unwrap().set_override("mode", "env:local dev").unwrap().build().unwrap();
+        let resolved = resolve_secrets(literal).unwrap();
+        assert_eq!(resolved.get_string("note").unwrap(), "env:REBT_SECRET_NOT_EXISTS_FOR_TEST");
+        assert_eq!(resolved.get_string("mode").unwrap(), "env:local dev");
+    }
+
+    #[test]
+    fn test_default_backend() {
+        let backend = |kind: BackendKind, default: bool| Backend { kind, readonly: false, connect: String::new(), default, replica_of: None, weight: None, pool: Pool::default(), options: None, namespace: None };
+        let mut backends = Dict::new();
+        backends.insert("main".to_string(), backend(BackendKind::Postgres, false));
+        backends.insert("analytics".to_string(), backend(BackendKind::Postgres, false));
+        backends.insert("cache".to_string(), backend(BackendKind::Redis, false));
+
+        let mut model = Model { backends: Some(backends) };
+        assert_eq!(model.default_name(true).as_deref(), Some("analytics"));
+        assert_eq!(model.default_name(false).as_deref(), Some("cache"));
+
+        model.backends.as_mut().unwrap().get_mut("main").unwrap().default = true;
+        assert_eq!(model.default_name(true).as_deref(), Some("main"));
+        assert_eq!(Model { backends: None }.default_name(true), None);
+    }
+
     /// test extends
     #[tokio::test]
     async fn test_extends() {
         let tests_load = format!("{}/tests/using-test-config.yml", project_dir().to_string_lossy());
-        let c = extends(&tests_load).await;
+        let c = extends::load_file(&tests_load).unwrap();
         println!("{:?}", c);
     }
 }
@@ -0,0 +1,158 @@
+//! Command line config arguments
+//!
+//! binaries built on `AppBuilder::with_args` accept:
+//! * `--config-dir <dir>` - config directory, overrides `REBT_CONFIG_PATH`
+//! * `--mode <mode>` - run mode, overrides `REBT_RUN_MODE`
+//! * `--set <key.path=value>` - override a value over files and environment, repeatable
+//! * `--print-config` - print the effective config, secrets redacted and every value annotated with its layer
+//!
+//! both `--name value` and `--name=value` are accepted, other arguments are kept in `ConfigArgs::rest`.
+
+use crate::conf::{layer_of, reload, secret, ConfigLayer};
+use crate::erx::{Erx, ResultBoxedEX};
+use config::Config;
+use std::collections::BTreeMap;
+use std::sync::OnceLock;
+
+/// layer name of `--set` overrides
+pub const SET_LAYER: &str = "--set";
+
+/// layer name of values no source provided
+const DEFAULT_LAYER: &str = "default";
+
+/// Config Args
+/// # Fields
+/// * `config_dir` - `--config-dir`
+/// * `mode` - `--mode`
+/// * `sets` - `--set` overrides in order, later ones win
+/// * `print_config` - `--print-config`
+/// * `rest` - arguments not recognized, left to the binary
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ConfigArgs {
+    pub config_dir: Option<String>,
+    pub mode: Option<String>,
+    pub sets: Vec<(String, String)>,
+    pub print_config: bool,
+    pub rest: Vec<String>,
+}
+
+impl ConfigArgs {
+    /// parse arguments, the program name excluded
+    /// # Returns
+    /// * `Err(reason)` - an option misses its value, or `--set` is not `key=value`
+    pub fn parse<I, S>(args: I) -> Result<Self, String>
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        let mut parsed = ConfigArgs::default();
+        let mut args = args.into_iter().map(Into::into);
+
+        while let Some(arg) = args.next() {
+            let (name, inline) = match arg.split_once('=') {
+                Some((name, value)) if name.starts_with("--") => (name.to_string(), Some(value.to_string())),
+                _ => (arg.clone(), None),
+            };
+
+            let mut value = |name: &str| inline.clone().or_else(|| args.next()).ok_or(format!("{} expects a value", name));
+            match name.as_str() {
+                "--config-dir" => parsed.config_dir = Some(value("--config-dir")?),
+                "--mode" => parsed.mode = Some(value("--mode")?),
+                "--set" => {
+                    let set = value("--set")?;
+                    match set.split_once('=') {
+                        Some((key, v)) if !key.trim().is_empty() => parsed.sets.push((key.trim().to_string(), v.to_string())),
+                        _ => return Err(format!("--set expects key.path=value, got '{}'", set)),
+                    }
+                },
+                "--print-config" => parsed.print_config = true,
+                _ => parsed.rest.push(arg),
+            }
+        }
+
+        Ok(parsed)
+    }
+
+    /// parse arguments of this process
+    pub fn from_env() -> Result<Self, String> {
+        Self::parse(std::env::args().skip(1))
+    }
+}
+
+static INSTALLED: OnceLock<ConfigArgs> = OnceLock::new();
+
+/// install args for config building, call it before config is first read
+/// # Returns
+/// * `Err` - args already installed, or config already read
+pub fn install(args: ConfigArgs) -> ResultBoxedEX {
+    if super::config_read() {
+        return Err(Erx::boxed("config already read, config args must be installed before"));
+    }
+    INSTALLED.set(args).map_err(|_| Erx::boxed("config args already installed"))
+}
+
+/// installed args, if any
+pub fn installed() -> Option<&'static ConfigArgs> {
+    INSTALLED.get()
+}
+
+/// effective config, one `key = value  # layer` line per value, sorted by key, secrets redacted
+/// # Arguments
+/// * `config` - the effective config
+/// * `layers` - the layers it was built from, see `conf::config_layers`
+pub fn print_config(config: &Config, layers: &[ConfigLayer]) -> String {
+    let mut dump = config.clone().try_deserialize::<serde_json::Value>().unwrap_or_default();
+    secret::redact_json(&mut dump);
+
+    let mut flat = BTreeMap::new();
+    reload::flatten("", &dump, &mut flat);
+
+    flat.iter()
+        .map(|(key, value)| {
+            format!("{} = {}  # {}", key, value, layer_of(layers, key).unwrap_or(DEFAULT_LAYER))
+        })
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let args = ConfigArgs::parse(["--mode", "production", "--config-dir=/etc/rings", "--set", "web.api.port=8081", "--set=log.level=debug", "--print-config", "serve"]).unwrap();
+        assert_eq!(args.mode.as_deref(), Some("production"));
+        assert_eq!(args.config_dir.as_deref(), Some("/etc/rings"));
+        assert_eq!(args.sets, vec![("web.api.port".to_string(), "8081".to_string()), ("log.level".to_string(), "debug".to_string())]);
+        assert!(args.print_config);
+        assert_eq!(args.rest, vec!["serve"]);
+
+        assert!(ConfigArgs::parse(["--mode"]).is_err());
+        assert!(ConfigArgs::parse(["--set", "no-value"]).is_err());
+    }
+
+    #[test]
+    fn test_install_after_read() {
+        let _ = crate::conf::settings();
+        let args = ConfigArgs { mode: Some("production".to_string()), ..Default::default() };
+        assert!(install(args).unwrap_err().message().contains("already read"));
+        assert!(installed().is_none());
+    }
+
+    #[test]
+    fn test_print_config() {
+        let file = Config::builder()
+            .add_source(config::File::from_str("name: logos\nmodel:\n  backends:\n    pg:\n      connect: \"postgres://a:b@c/d\"\n", config::FileFormat::Yaml))
+            .build()
+            .unwrap();
+        let sets = Config::builder().set_override("name", "cli").unwrap().build().unwrap();
+        let config = Config::builder().add_source(file.clone()).add_source(sets.clone()).set_default("debug", false).unwrap().build().unwrap();
+        let layers = vec![ConfigLayer::new("config/config.yml", file), ConfigLayer::new(SET_LAYER, sets)];
+
+        let printed = print_config(&config, &layers);
+        assert!(printed.contains("name = \"cli\"  # --set"));
+        assert!(printed.contains(&format!("model.backends.pg.connect = \"{}\"  # config/config.yml", secret::REDACTED)));
+        assert!(printed.contains("debug = false  # default"));
+    }
+}
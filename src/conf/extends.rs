@@ -0,0 +1,132 @@
+//! Extension configs
+//!
+//! extra config files named in `Rebit::extends`, e.g.
+//! ```yaml
+//! extends:
+//!   providers: config/providers.yml
+//! ```
+//! loaded on first access, typed with `extends::get::<T>("providers")`, and reloaded with `extends::reload`.
+
+use crate::conf::{rebit, resolve_secrets};
+use crate::erx::{Erx, ResultBoxedE};
+use config::Config;
+use serde::de::DeserializeOwned;
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+use tracing::{error, info};
+
+/// loaded extension configs, keyed by extends name
+static LOADED: RwLock<Option<HashMap<String, Arc<Config>>>> = RwLock::new(None);
+
+fn cached(name: &str) -> Option<Arc<Config>> {
+    LOADED.read().ok()?.as_ref()?.get(name).cloned()
+}
+
+fn cache(name: &str, config: Arc<Config>) {
+    if let Ok(mut loaded) = LOADED.write() {
+        loaded.get_or_insert_with(HashMap::new).insert(name.to_string(), config);
+    }
+}
+
+/// names of every configured extension
+pub async fn names() -> Vec<String> {
+    let mut names: Vec<String> = rebit().read().await.extends.iter().flatten().map(|(name, _)| name.clone()).collect();
+    names.sort();
+    names
+}
+
+/// file of an extension name, from `Rebit::extends`
+pub async fn file_of(name: &str) -> ResultBoxedE<String> {
+    rebit().read().await.get_extend(name).ok_or(Erx::boxed(&format!("extends '{}' is not configured", name)))
+}
+
+/// load a config file, secret references resolved
+/// # Arguments
+/// * `file` - config file path, the extension can be omitted
+pub fn load_file(file: &str) -> ResultBoxedE<Config> {
+    let built = Config::builder().add_source(config::File::with_name(file).required(true)).build().and_then(resolve_secrets);
+    built.map_err(|ex| Erx::boxed(&format!("extends file '{}' load failed: {}", file, ex)))
+}
+
+/// config of an extension, loaded on first access
+pub async fn config(name: &str) -> ResultBoxedE<Arc<Config>> {
+    if let Some(config) = cached(name) {
+        return Ok(config);
+    }
+
+    let config = Arc::new(load_file(&file_of(name).await?)?);
+    cache(name, Arc::clone(&config));
+    Ok(config)
+}
+
+/// the whole extension config as `T`
+///
+/// # Example
+/// ```ignore
+/// let providers: Providers = rings::conf::extends::get::<Providers>("providers").await?;
+/// ```
+pub async fn get<T: DeserializeOwned>(name: &str) -> ResultBoxedE<T> {
+    let config = config(name).await?;
+    (*config).clone().try_deserialize::<T>().map_err(|ex| Erx::boxed(&format!("extends '{}' deserialize failed: {}", name, ex)))
+}
+
+/// a key of the extension config as `T`
+/// # Arguments
+/// * `name` - extends name
+/// * `key` - dotted key path, e.g. `cnpc.api.limit`
+pub async fn get_key<T: DeserializeOwned>(name: &str, key: &str) -> ResultBoxedE<T> {
+    config(name).await?.get::<T>(key).map_err(|ex| Erx::boxed(&format!("extends '{}' key '{}': {}", name, key, ex)))
+}
+
+/// reload an extension from its file, the loaded one is kept when failed
+pub async fn reload(name: &str) -> ResultBoxedE<Arc<Config>> {
+    let config = Arc::new(load_file(&file_of(name).await?)?);
+    cache(name, Arc::clone(&config));
+    info!("extends '{}' reloaded", name);
+    Ok(config)
+}
+
+/// reload every extension loaded before, and forget the ones no longer configured
+/// # Returns
+/// * `Vec<String>` - names failed to reload
+pub async fn reload_all() -> Vec<String> {
+    let configured = names().await;
+    let loaded: Vec<String> = match LOADED.write() {
+        Ok(mut loaded) => {
+            let loaded = loaded.get_or_insert_with(HashMap::new);
+            loaded.retain(|name, _| configured.contains(name));
+            loaded.keys().cloned().collect()
+        },
+        Err(_) => vec![],
+    };
+
+    let mut failed = vec![];
+    for name in loaded {
+        if let Err(ex) = reload(&name).await {
+            error!("extends '{}' reload failed, keep the loaded one: {}", name, ex.message());
+            failed.push(name);
+        }
+    }
+    failed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tools::tests::tools::project_dir;
+
+    #[test]
+    fn test_load_file() {
+        let file = format!("{}/tests/using-test-config.yml", project_dir().to_string_lossy());
+        let config = load_file(&file).unwrap();
+        assert_eq!(config.get_string("providers.cnpc.api.merchant").unwrap(), "iusworks");
+
+        assert!(load_file("/not/exists/extends.yml").unwrap_err().message().contains("/not/exists/extends.yml"));
+    }
+
+    #[tokio::test]
+    async fn test_not_configured() {
+        let ex = get::<HashMap<String, String>>("not-configured").await.unwrap_err();
+        assert!(ex.message().contains("'not-configured' is not configured"));
+    }
+}
@@ -0,0 +1,212 @@
+use crate::conf::{build_config, config_files, rebit, section, settings, validate, Rebit};
+use crate::erx::{Erx, ResultBoxedE};
+use config::Config;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::OnceLock;
+use std::time::{Duration, SystemTime};
+use tokio::sync::{broadcast, Mutex};
+use tracing::{error, info, warn};
+
+/// changes kept for slow subscribers
+const CONFIG_CHANGE_CAPACITY: usize = 64;
+
+/// default interval of `watch` checking config files
+pub const CONFIG_WATCH_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Config Change
+/// # Fields
+/// * `version` - config version, increased on every applied reload
+/// * `keys` - changed keys in dotted path, e.g. `log.level`, sorted
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ConfigChange {
+    pub version: u64,
+    pub keys: Vec<String>,
+}
+
+impl ConfigChange {
+    /// key or any key under it changed
+    /// # Arguments
+    /// * `key` - dotted path, e.g. `log` or `web.api.middleware`
+    pub fn changed(&self, key: &str) -> bool {
+        self.keys.iter().any(|k| k == key || k.starts_with(&format!("{}.", key)) || key.starts_with(&format!("{}.", k)))
+    }
+}
+
+static VERSION: AtomicU64 = AtomicU64::new(0);
+
+fn sender() -> &'static broadcast::Sender<ConfigChange> {
+    static SENDER: OnceLock<broadcast::Sender<ConfigChange>> = OnceLock::new();
+    SENDER.get_or_init(|| broadcast::channel(CONFIG_CHANGE_CAPACITY).0)
+}
+
+/// subscribe config changes applied from now on
+pub fn subscribe() -> broadcast::Receiver<ConfigChange> {
+    sender().subscribe()
+}
+
+/// current config version, 0 until the first applied reload
+pub fn version() -> u64 {
+    VERSION.load(Ordering::SeqCst)
+}
+
+/// deserialize and validate rebit from config, same rules as startup
+pub fn prepare(config: &Config) -> ResultBoxedE<Rebit> {
+    validate::load(config).map_err(|report| Erx::boxed(&report.to_string()))
+}
+
+/// flatten config into dotted keys, arrays are compared as a whole
+pub(crate) fn flatten(prefix: &str, value: &serde_json::Value, into: &mut std::collections::BTreeMap<String, serde_json::Value>) {
+    match value {
+        serde_json::Value::Object(map) if !map.is_empty() => {
+            for (k, v) in map {
+                let key = if prefix.is_empty() { k.clone() } else { format!("{}.{}", prefix, k) };
+                flatten(&key, v, into);
+            }
+        },
+        _ => {
+            into.insert(prefix.to_string(), value.clone());
+        },
+    }
+}
+
+/// keys added, removed or modified between two configs, sorted
+pub fn diff(old: &Config, new: &Config) -> Vec<String> {
+    let flat = |config: &Config| {
+        let mut into = std::collections::BTreeMap::new();
+        if let Ok(value) = config.clone().try_deserialize::<serde_json::Value>() {
+            flatten("", &value, &mut into);
+        }
+        into
+    };
+
+    let (old, new) = (flat(old), flat(new));
+    let mut keys: Vec<String> = old.keys().chain(new.keys()).filter(|k| old.get(*k) != new.get(*k)).cloned().collect();
+    keys.sort();
+    keys.dedup();
+    keys
+}
+
+/// apply a built config: validate it, swap settings, rebit and registered sections, then notify subscribers.
+/// a config failing validation is rejected and the previous one kept.
+///
+/// # Returns
+/// * `ConfigChange` - the applied change, empty keys when nothing changed
+pub async fn apply(config: Config) -> ResultBoxedE<ConfigChange> {
+    static APPLYING: Mutex<()> = Mutex::const_new(());
+    let _applying = APPLYING.lock().await;
+
+    let prepared = prepare(&config).map_err(|ex| {
+        error!("config reload rejected, keep the previous one: {}", ex.message());
+        Erx::boxed(&format!("config reload rejected: {}", ex.message()))
+    })?;
+
+    let keys = diff(&*settings().read().await, &config);
+    if keys.is_empty() {
+        info!("config reloaded, nothing changed");
+        return Ok(ConfigChange { version: version(), keys });
+    }
+
+    *settings().write().await = config;
+    *rebit().write().await = prepared;
+    section::refresh(&*settings().read().await);
+
+    let change = ConfigChange { version: VERSION.fetch_add(1, Ordering::SeqCst) + 1, keys };
+    info!("config reloaded, version: {} changed: {:?}", change.version, change.keys);
+    let _ = sender().send(change.clone());
+
+    Ok(change)
+}
+
+/// rebuild config from files and `REBT_*` environment, then `apply` it.
+/// loaded extension configs are reloaded too, see `extends::reload_all`
+pub async fn reload() -> ResultBoxedE<ConfigChange> {
+    let config = build_config().map_err(|ex| {
+        error!("config reload failed to build, keep the previous one: {}", ex);
+        Erx::boxed(&format!("config reload failed to build: {}", ex))
+    })?;
+
+    let change = apply(config).await?;
+    crate::conf::extends::reload_all().await;
+    Ok(change)
+}
+
+/// modified time of every config file, missing files are `None`
+fn stamps() -> Vec<Option<SystemTime>> {
+    config_files().iter().map(|f| std::fs::metadata(f).and_then(|m| m.modified()).ok()).collect()
+}
+
+/// watch config files, reload when any of them created, modified or removed
+/// # Arguments
+/// * `interval` - checking interval, `CONFIG_WATCH_INTERVAL` is a good default
+/// # Returns
+/// * `JoinHandle` - abort it to stop watching
+pub fn watch(interval: Duration) -> tokio::task::JoinHandle<()> {
+    tokio::spawn(async move {
+        info!("watching config files: {:?}", config_files());
+        let mut last = stamps();
+        loop {
+            tokio::time::sleep(interval).await;
+            let current = stamps();
+            if current == last {
+                continue;
+            }
+
+            last = current;
+            if let Err(ex) = reload().await {
+                warn!("config files changed but not applied: {}", ex.message());
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn yaml(content: &str) -> Config {
+        Config::builder().add_source(config::File::from_str(content, config::FileFormat::Yaml)).build().unwrap()
+    }
+
+    const BASE: &str = r#"
+name: logos
+short: LOGO
+debug: false
+web:
+  api:
+    port: 8080
+model:
+  backends: ~
+log:
+  level: info
+  console: true
+  dirs: ""
+"#;
+
+    #[test]
+    fn test_diff() {
+        let old = yaml(BASE);
+        let new = yaml(&BASE.replace("level: info", "level: debug").replace("port: 8080", "port: 8081"));
+        assert_eq!(diff(&old, &new), vec!["log.level", "web.api.port"]);
+        assert!(diff(&old, &yaml(BASE)).is_empty());
+
+        let added = yaml(&format!("{}\nshutdown:\n  deadline: 10\n", BASE));
+        assert_eq!(diff(&old, &added), vec!["shutdown.deadline"]);
+    }
+
+    #[test]
+    fn test_change_matching() {
+        let change = ConfigChange { version: 1, keys: vec!["log.level".to_string(), "web.api.middleware.limitor.rules".to_string()] };
+        assert!(change.changed("log"));
+        assert!(change.changed("log.level"));
+        assert!(change.changed("web.api.middleware"));
+        assert!(!change.changed("log.dirs"));
+        assert!(!change.changed("logs"));
+    }
+
+    #[test]
+    fn test_prepare_rejects_invalid() {
+        assert!(prepare(&yaml(BASE)).is_ok());
+        assert!(prepare(&yaml(&BASE.replace("port: 8080", "port: 0"))).is_err());
+        assert!(prepare(&yaml("name: logos")).is_err());
+    }
+}
@@ -0,0 +1,145 @@
+//! Sample config generation
+//!
+//! `sample()` renders the defaults of `Rebit` and of every registered section as yaml,
+//! a starting point for `config/config.yml`.
+
+use crate::conf::{secret, section, Rebit};
+use serde_json::Value;
+
+/// merge `from` into `into`, objects merged key by key, others replaced
+fn merge(into: &mut Value, from: Value) {
+    match (into, from) {
+        (Value::Object(into), Value::Object(from)) => {
+            for (k, v) in from {
+                match into.get_mut(&k) {
+                    Some(existing) => merge(existing, v),
+                    None => {
+                        into.insert(k, v);
+                    },
+                }
+            }
+        },
+        (into, from) => *into = from,
+    }
+}
+
+/// scalar as yaml, strings double quoted (json escaping is valid yaml)
+fn scalar(value: &Value) -> String {
+    match value {
+        Value::Null => "~".to_string(),
+        Value::Object(_) => "{}".to_string(),
+        Value::Array(_) => "[]".to_string(),
+        other => other.to_string(),
+    }
+}
+
+/// key as yaml, plain when safe, double quoted otherwise (`:`, `#`, spaces, leading `-`, `null`, `true`, ...)
+fn key(name: &str) -> String {
+    const RESERVED: [&str; 9] = ["~", "null", "true", "false", "yes", "no", "on", "off", "y"];
+    let plain = name.chars().next().is_some_and(|c| c.is_ascii_alphanumeric() || c == '_')
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+        && !RESERVED.iter().any(|r| r.eq_ignore_ascii_case(name));
+    if plain {
+        name.to_string()
+    } else {
+        Value::String(name.to_string()).to_string()
+    }
+}
+
+fn nested(value: &Value) -> bool {
+    match value {
+        Value::Object(map) => !map.is_empty(),
+        Value::Array(items) => !items.is_empty(),
+        _ => false,
+    }
+}
+
+/// render value as yaml block
+fn yaml(value: &Value, indent: usize, out: &mut String) {
+    let pad = " ".repeat(indent);
+    match value {
+        Value::Object(map) => {
+            for (k, v) in map {
+                if nested(v) {
+                    out.push_str(&format!("{}{}:\n", pad, key(k)));
+                    yaml(v, indent + 2, out);
+                } else {
+                    out.push_str(&format!("{}{}: {}\n", pad, key(k), scalar(v)));
+                }
+            }
+        },
+        Value::Array(items) => {
+            for item in items {
+                if nested(item) {
+                    out.push_str(&format!("{}-\n", pad));
+                    yaml(item, indent + 2, out);
+                } else {
+                    out.push_str(&format!("{}- {}\n", pad, scalar(item)));
+                }
+            }
+        },
+        other => out.push_str(&format!("{}{}\n", pad, scalar(other))),
+    }
+}
+
+/// sample config in yaml: `Rebit` defaults and every registered section, secrets redacted
+pub fn sample() -> String {
+    let mut value = serde_json::to_value(Rebit::default()).unwrap_or_default();
+    for defaults in section::defaults() {
+        merge(&mut value, defaults);
+    }
+    secret::redact_json(&mut value);
+
+    let mut out = String::new();
+    yaml(&value, 0, &mut out);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use config::{Config, FileFormat};
+
+    #[test]
+    fn test_yaml() {
+        let value = serde_json::json!({"name": "logos", "web": {"api": {"port": 8080, "bind": null}}, "tags": ["a", "b"], "empty": {}});
+        let mut out = String::new();
+        yaml(&value, 0, &mut out);
+
+        let parsed: Value = Config::builder().add_source(config::File::from_str(&out, FileFormat::Yaml)).build().unwrap().try_deserialize().unwrap();
+        assert_eq!(parsed["web"]["api"]["port"], 8080);
+        assert_eq!(parsed["tags"][1], "b");
+    }
+
+    #[test]
+    fn test_yaml_escaping() {
+        let tricky = ["a: b # c", "line\nbreak", "- leading", "#hash", "*ref", "&anchor", "'single'", "\"double\"", "tab\there", "  spaced  ", "null", "true", "~"];
+        let mut value = serde_json::Map::new();
+        for (i, text) in tricky.iter().enumerate() {
+            value.insert(format!("v{}", i), Value::String(text.to_string()));
+            value.insert(text.to_string(), Value::from(i));
+        }
+        let value = Value::Object(value);
+        let mut out = String::new();
+        yaml(&value, 0, &mut out);
+
+        let parsed: Value = Config::builder().add_source(config::File::from_str(&out, FileFormat::Yaml)).build().unwrap().try_deserialize().unwrap();
+        for (i, text) in tricky.iter().enumerate() {
+            assert_eq!(parsed[format!("v{}", i)], *text, "value {:?}", text);
+            assert_eq!(parsed[*text], i, "key {:?}", text);
+        }
+    }
+
+    #[test]
+    fn test_sample_round_trip() {
+        let parsed: Value = Config::builder().add_source(config::File::from_str(&sample(), FileFormat::Yaml)).build().unwrap().try_deserialize().unwrap();
+        assert_eq!(parsed["name"], "Rings");
+    }
+
+    #[test]
+    fn test_sample() {
+        let sample = sample();
+        assert!(sample.contains("name: \"Rings\""));
+        assert!(sample.contains("model:\n  backends: ~\n"));
+    }
+}
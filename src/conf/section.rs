@@ -0,0 +1,224 @@
+//! Typed config sections
+//!
+//! downstream crates own their config section instead of reading strings:
+//! ```ignore
+//! #[derive(Serialize, Deserialize, Default, Clone)]
+//! struct Signator { redis_url: String, nonce_lifetime: i64 }
+//!
+//! impl rings::conf::section::Section for Signator {
+//!     const KEY: &'static str = "signator";
+//! }
+//!
+//! let signator = rings::conf::section::<Signator>().await?;
+//! ```
+//! missing fields fall back to the `Default` of the section, registered sections are validated with
+//! the rest of the config, refreshed on reload and included in `conf::sample()`.
+
+use crate::conf::settings;
+use crate::conf::validate::{deserialize_problem, ConfigProblem, ConfigReport};
+use crate::erx::{Erx, ResultBoxedE};
+use config::Config;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::any::{Any, TypeId};
+use std::sync::{Arc, RwLock};
+use tracing::warn;
+
+/// Config Section
+pub trait Section: Serialize + DeserializeOwned + Default + Send + Sync + 'static {
+    /// dotted key the section lives under, e.g. `signator` or `providers.cnpc`
+    const KEY: &'static str;
+
+    /// problems deserializing can not tell, keys relative to `KEY`
+    fn problems(&self) -> Vec<ConfigProblem> {
+        vec![]
+    }
+}
+
+type Loaded = Arc<dyn Any + Send + Sync>;
+
+/// registered section
+struct Entry {
+    key: &'static str,
+    type_id: TypeId,
+    load: fn(&Config) -> Result<Loaded, Vec<ConfigProblem>>,
+    defaults: fn() -> serde_json::Value,
+}
+
+static ENTRIES: RwLock<Vec<Entry>> = RwLock::new(Vec::new());
+
+static LOADED: RwLock<Vec<(TypeId, Loaded)>> = RwLock::new(Vec::new());
+
+/// nest value under a dotted key, `a.b` + `v` => `{"a": {"b": v}}`
+fn nest(key: &str, value: serde_json::Value) -> serde_json::Value {
+    key.rsplit('.').fold(value, |value, part| serde_json::json!({ part: value }))
+}
+
+fn defaults_of<T: Section>() -> serde_json::Value {
+    serde_json::to_value(T::default()).unwrap_or_default()
+}
+
+/// load section `T` from config, missing fields filled with the section defaults
+fn load_section<T: Section>(config: &Config) -> Result<T, Vec<ConfigProblem>> {
+    let defaults = nest(T::KEY, defaults_of::<T>()).to_string();
+    let merged = Config::builder()
+        .add_source(config::File::from_str(&defaults, config::FileFormat::Json))
+        .add_source(config.clone())
+        .build()
+        .and_then(|merged| merged.get::<T>(T::KEY))
+        .map_err(|ex| {
+            let mut problem = deserialize_problem(ex);
+            if problem.key.is_empty() {
+                problem.key = T::KEY.to_string();
+            }
+            vec![problem]
+        })?;
+
+    let problems: Vec<ConfigProblem> = merged
+        .problems()
+        .into_iter()
+        .map(|mut problem| {
+            problem.key = if problem.key.is_empty() { T::KEY.to_string() } else { format!("{}.{}", T::KEY, problem.key) };
+            problem
+        })
+        .collect();
+    if !problems.is_empty() {
+        return Err(problems);
+    }
+
+    Ok(merged)
+}
+
+fn load_any<T: Section>(config: &Config) -> Result<Loaded, Vec<ConfigProblem>> {
+    load_section::<T>(config).map(|section| Arc::new(section) as Loaded)
+}
+
+fn store(type_id: TypeId, loaded: Loaded) {
+    if let Ok(mut all) = LOADED.write() {
+        all.retain(|(id, _)| *id != type_id);
+        all.push((type_id, loaded));
+    }
+}
+
+/// register section `T`, so that it is validated, refreshed on reload and sampled.
+/// `section::<T>()` registers it as well, register early to validate it at startup.
+pub fn register<T: Section>() {
+    let Ok(mut entries) = ENTRIES.write() else {
+        return;
+    };
+
+    let type_id = TypeId::of::<T>();
+    if entries.iter().any(|e| e.type_id == type_id) {
+        return;
+    }
+    if entries.iter().any(|e| e.key == T::KEY) {
+        warn!("config section '{}' registered by more than one type, latest: {}", T::KEY, std::any::type_name::<T>());
+    }
+
+    entries.push(Entry { key: T::KEY, type_id, load: load_any::<T>, defaults: defaults_of::<T> });
+}
+
+/// keys of every registered section
+pub fn keys() -> Vec<&'static str> {
+    ENTRIES.read().map(|entries| entries.iter().map(|e| e.key).collect()).unwrap_or_default()
+}
+
+/// typed section `T` of the current config
+pub async fn section<T: Section>() -> ResultBoxedE<Arc<T>> {
+    register::<T>();
+
+    let type_id = TypeId::of::<T>();
+    let found = LOADED.read().ok().and_then(|all| all.iter().find(|(id, _)| *id == type_id).map(|(_, loaded)| Arc::clone(loaded)));
+    if let Some(found) = found {
+        return found.downcast::<T>().map_err(|_| Erx::boxed(&format!("config section '{}' type mismatch", T::KEY)));
+    }
+
+    let loaded = Arc::new(load_section::<T>(&*settings().read().await).map_err(|problems| Erx::boxed(&ConfigReport { problems }.to_string()))?);
+    store(type_id, Arc::clone(&loaded) as Loaded);
+    Ok(loaded)
+}
+
+/// problems of every registered section in config
+pub(crate) fn problems(config: &Config) -> Vec<ConfigProblem> {
+    let Ok(entries) = ENTRIES.read() else {
+        return vec![];
+    };
+    entries.iter().filter_map(|e| (e.load)(config).err()).flatten().collect()
+}
+
+/// reload every registered section from config, sections failing to load keep the previous value
+pub(crate) fn refresh(config: &Config) {
+    let Ok(entries) = ENTRIES.read() else {
+        return;
+    };
+    for entry in entries.iter() {
+        match (entry.load)(config) {
+            Ok(loaded) => store(entry.type_id, loaded),
+            Err(problems) => warn!("config section '{}' not refreshed: {}", entry.key, ConfigReport { problems }),
+        }
+    }
+}
+
+/// defaults of every registered section, nested under their keys
+pub(crate) fn defaults() -> Vec<serde_json::Value> {
+    let Ok(entries) = ENTRIES.read() else {
+        return vec![];
+    };
+    entries.iter().map(|e| nest(e.key, (e.defaults)())).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Deserialize;
+
+    #[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+    #[serde(default)]
+    struct Limits {
+        max: u32,
+        window: u64,
+    }
+
+    impl Default for Limits {
+        fn default() -> Self {
+            Limits { max: 600, window: 60 }
+        }
+    }
+
+    impl Section for Limits {
+        const KEY: &'static str = "section_tests.limits";
+
+        fn problems(&self) -> Vec<ConfigProblem> {
+            match self.window {
+                0 => vec![ConfigProblem::new("window", "must not be 0")],
+                _ => vec![],
+            }
+        }
+    }
+
+    fn yaml(content: &str) -> Config {
+        Config::builder().add_source(config::File::from_str(content, config::FileFormat::Yaml)).build().unwrap()
+    }
+
+    #[test]
+    fn test_load_section() {
+        let loaded = load_section::<Limits>(&yaml("section_tests:\n  limits:\n    max: 10\n")).unwrap();
+        assert_eq!(loaded, Limits { max: 10, window: 60 });
+
+        let missing = load_section::<Limits>(&yaml("name: logos\n")).unwrap();
+        assert_eq!(missing, Limits::default());
+
+        let errs = load_section::<Limits>(&yaml("section_tests:\n  limits:\n    window: 0\n")).unwrap_err();
+        assert_eq!(errs[0].key, "section_tests.limits.window");
+
+        register::<Limits>();
+        assert!(keys().contains(&"section_tests.limits"));
+        assert_eq!(problems(&yaml("section_tests:\n  limits:\n    max: many\n")).len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_section() {
+        let limits = section::<Limits>().await.unwrap();
+        assert_eq!(*limits, Limits::default());
+    }
+}
@@ -0,0 +1,406 @@
+use crate::conf::{config_layers, layer_of, BackendKind, ConfigLayer, Rebit};
+use crate::erx::{Erx, ResultBoxedEX};
+use config::{Config, ConfigError};
+use std::fmt;
+
+/// Config Problem
+/// # Fields
+/// * `key` - dotted key path, e.g. `web.api.port`, empty when not bound to a key
+/// * `message` - what is wrong
+/// * `source` - file (or environment) the value came from, if known
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConfigProblem {
+    pub key: String,
+    pub message: String,
+    pub source: Option<String>,
+}
+
+impl ConfigProblem {
+    pub fn new(key: &str, message: &str) -> Self {
+        Self { key: key.to_string(), message: message.to_string(), source: None }
+    }
+}
+
+impl fmt::Display for ConfigProblem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.key.is_empty() {
+            write!(f, "{}", self.message)?;
+        } else {
+            write!(f, "{}: {}", self.key, self.message)?;
+        }
+        if let Some(source) = &self.source {
+            write!(f, " (from {})", source)?;
+        }
+        Ok(())
+    }
+}
+
+/// Config Report, every problem found in one pass
+#[derive(Debug, Clone, Default)]
+pub struct ConfigReport {
+    pub problems: Vec<ConfigProblem>,
+}
+
+impl ConfigReport {
+    pub fn is_empty(&self) -> bool {
+        self.problems.is_empty()
+    }
+
+    /// fill problem sources from the layer setting the key, see `conf::layer_of`
+    fn locate(mut self, layers: &[ConfigLayer]) -> Self {
+        for problem in self.problems.iter_mut().filter(|p| p.source.is_none() && !p.key.is_empty()) {
+            problem.source = layer_of(layers, &problem.key).map(String::from);
+        }
+        self
+    }
+}
+
+impl fmt::Display for ConfigReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "config invalid, {} problem(s):", self.problems.len())?;
+        for problem in &self.problems {
+            write!(f, "\n  - {}", problem)?;
+        }
+        Ok(())
+    }
+}
+
+/// problem of a failed deserializing, the first one serde met
+pub(crate) fn deserialize_problem(error: ConfigError) -> ConfigProblem {
+    match error {
+        ConfigError::At { error, origin, key } => {
+            let mut problem = deserialize_problem(*error);
+            problem.key = key.unwrap_or(problem.key);
+            problem.source = origin.or(problem.source);
+            problem
+        },
+        ConfigError::Type { origin, unexpected, expected, key } => ConfigProblem {
+            key: key.unwrap_or_default(),
+            message: format!("invalid type: {}, expected {}", unexpected, expected),
+            source: origin,
+        },
+        ConfigError::FileParse { uri, cause } => ConfigProblem { key: String::new(), message: format!("unable to parse: {}", cause), source: uri },
+        ConfigError::NotFound(key) => ConfigProblem::new(&key, "not found"),
+        other => ConfigProblem::new("", &other.to_string()),
+    }
+}
+
+/// deserialize `Rebit` from config and validate it with every registered section,
+/// problems are reported with their key path and source file.
+/// problems are accepted with a warning when `debug` is on, deserializing failure never is.
+///
+/// # Arguments
+/// * `config` - The config to load from, built by `conf::build_config`.
+///
+/// # Returns
+/// * `Result<Rebit, ConfigReport>` - The rebit, or every problem found.
+pub fn load(config: &Config) -> Result<Rebit, ConfigReport> {
+    load_with(config, &config_layers())
+}
+
+/// `load` with the layers the config was built from, problems located in them
+pub fn load_with(config: &Config, layers: &[ConfigLayer]) -> Result<Rebit, ConfigReport> {
+    let rebit = match config.clone().try_deserialize::<Rebit>() {
+        Ok(rebit) => rebit,
+        Err(error) => return Err(ConfigReport { problems: vec![deserialize_problem(error)] }.locate(layers)),
+    };
+
+    let mut problems = rebit.problems();
+    problems.extend(crate::conf::section::problems(config));
+    problems.sort_by(|a, b| a.key.cmp(&b.key));
+
+    let report = ConfigReport { problems }.locate(layers);
+    if report.is_empty() {
+        return Ok(rebit);
+    }
+
+    if rebit.debug {
+        // logging may not be initialized yet, it depends on rebit
+        eprintln!("[debug mode, continue anyway] {}", report);
+        tracing::warn!("[debug mode, continue anyway] {}", report);
+        return Ok(rebit);
+    }
+
+    Err(report)
+}
+
+/// scheme a connect string of the backend kind must start with
+fn backend_schemes(kind: &BackendKind) -> &'static [&'static str] {
+    match kind {
+        BackendKind::Redis => &["redis://", "rediss://", "redis+unix://", "unix://"],
+        BackendKind::Postgres => &["postgres://", "postgresql://"],
+        BackendKind::Sqlite => &["sqlite:"],
+        BackendKind::MySQL => &["mysql://"],
+    }
+}
+
+/// two hosts bound on one port conflict when the same, or either is a wildcard taking every address
+fn binds_conflict(a: &str, b: &str) -> bool {
+    let wildcard = |host: &str| host.parse::<std::net::IpAddr>().is_ok_and(|ip| ip.is_unspecified());
+    a.eq(b) || wildcard(a) || wildcard(b)
+}
+
+/// problems of a database pool
+fn pool_problems(key: &str, pool: &crate::conf::Pool) -> Vec<ConfigProblem> {
+    let mut problems = vec![];
+    if pool.max_connections == 0 {
+        problems.push(ConfigProblem::new(&format!("{}.max_connections", key), "must not be 0"));
+    }
+    if pool.min_connections > pool.max_connections {
+        problems.push(ConfigProblem::new(&format!("{}.min_connections", key), &format!("must not exceed max_connections {}", pool.max_connections)));
+    }
+    for (field, seconds) in [("connect_timeout", pool.connect_timeout), ("acquire_timeout", pool.acquire_timeout)] {
+        if seconds == 0 {
+            problems.push(ConfigProblem::new(&format!("{}.{}", key, field), "must not be 0"));
+        }
+    }
+    if pool.statement_log_level().is_none() {
+        problems.push(ConfigProblem::new(&format!("{}.statement_log", key), &format!("'{}' is not off, error, warn, info, debug or trace", pool.statement_log)));
+    }
+    problems
+}
+
+impl Rebit {
+    /// every problem deserializing can not tell, sorted by key
+    pub fn problems(&self) -> Vec<ConfigProblem> {
+        let mut problems: Vec<ConfigProblem> = vec![];
+
+        let mut webs: Vec<(&String, &crate::conf::Web)> = self.web.iter().collect();
+        webs.sort_by_key(|(name, _)| *name);
+
+        let mut binds: Vec<(String, String, u16)> = vec![];
+        for (name, web) in webs {
+            let key = format!("web.{}", name);
+            if web.port == 0 {
+                problems.push(ConfigProblem::new(&format!("{}.port", key), "must not be 0"));
+            }
+
+            if let Some(bind) = &web.bind {
+                if bind.parse::<std::net::IpAddr>().is_err() {
+                    problems.push(ConfigProblem::new(&format!("{}.bind", key), &format!("'{}' is not an ip address", bind)));
+                }
+            }
+
+            let host = web.bind.clone().unwrap_or("0.0.0.0".to_string());
+            if let Some((other, other_host, _)) = binds.iter().find(|(_, h, port)| *port == web.port && binds_conflict(h, &host)) {
+                problems.push(ConfigProblem::new(&format!("{}.port", key), &format!("{} conflicts with web.{} on {}:{}", web.bind_addr(), other, other_host, web.port)));
+            }
+            binds.push((name.clone(), host, web.port));
+
+            for (middleware, options) in web.middleware.iter().flatten() {
+                let key = format!("{}.middleware.{}", key, middleware);
+                for (option, value) in options.iter() {
+                    let option_key = format!("{}.{}", key, option);
+                    if option.eq("priority") && value.parse::<i32>().is_err() {
+                        problems.push(ConfigProblem::new(&option_key, &format!("'{}' is not an integer", value)));
+                    }
+                    if (option.eq("redis") || option.ends_with("redis_url")) && !backend_schemes(&BackendKind::Redis).iter().any(|s| value.starts_with(s))
+                    {
+                        problems.push(ConfigProblem::new(&option_key, "is not a redis url"));
+                    }
+                }
+            }
+        }
+
+        let mut backends: Vec<(&String, &crate::conf::Backend)> = self.model.backends.iter().flatten().collect();
+        backends.sort_by_key(|(name, _)| *name);
+        for database in [true, false] {
+            let defaults: Vec<&String> = backends.iter().filter(|(_, b)| b.default && b.kind.is_database() == database).map(|(name, _)| *name).collect();
+            if let [first, others @ ..] = defaults.as_slice() {
+                for other in others {
+                    problems.push(ConfigProblem::new(&format!("model.backends.{}.default", other), &format!("model.backends.{} is the default already", first)));
+                }
+            }
+        }
+
+        for (name, backend) in backends.iter() {
+            if let Some(primary) = &backend.replica_of {
+                let key = format!("model.backends.{}.replica_of", name);
+                match self.model.backends.iter().flatten().find(|(n, _)| *n == primary).map(|(_, b)| b) {
+                    None => problems.push(ConfigProblem::new(&key, &format!("backend '{}' not found", primary))),
+                    Some(p) if !p.kind.is_database() || p.kind != backend.kind => {
+                        problems.push(ConfigProblem::new(&key, &format!("'{}' is not a {} backend", primary, backend.kind)))
+                    },
+                    Some(p) if p.replica_of.is_some() => problems.push(ConfigProblem::new(&key, &format!("'{}' is a replica itself", primary))),
+                    Some(_) => {},
+                }
+                if !backend.readonly {
+                    problems.push(ConfigProblem::new(&format!("model.backends.{}.readonly", name), "a replica must be readonly"));
+                }
+            }
+        }
+
+        for (name, backend) in backends {
+            let key = format!("model.backends.{}.connect", name);
+            if backend.connect.trim().is_empty() {
+                problems.push(ConfigProblem::new(&key, "must not be empty"));
+                continue;
+            }
+
+            if backend.kind.is_database() {
+                problems.extend(pool_problems(&format!("model.backends.{}.pool", name), &backend.pool));
+            }
+
+            let schemes = backend_schemes(&backend.kind);
+            if !schemes.iter().any(|s| backend.connect.starts_with(s)) {
+                problems.push(ConfigProblem::new(&key, &format!("{} backend expects one of {:?}", backend.kind, schemes)));
+            }
+        }
+
+        if let Some(log) = &self.log {
+            if let Err(ex) = tracing_subscriber::EnvFilter::try_new(&log.level) {
+                problems.push(ConfigProblem::new("log.level", &ex.to_string()));
+            }
+
+            let dirs = log.dirs.trim();
+            if !dirs.is_empty() && !std::path::Path::new(dirs).is_dir() {
+                problems.push(ConfigProblem::new("log.dirs", &format!("'{}' is not a directory", dirs)));
+            }
+        }
+
+        if let Some(shutdown) = &self.shutdown {
+            if shutdown.deadline == 0 {
+                problems.push(ConfigProblem::new("shutdown.deadline", "must not be 0"));
+            }
+        }
+
+        problems.sort_by(|a, b| a.key.cmp(&b.key));
+        problems
+    }
+
+    /// validate values deserializing can not tell
+    /// # Returns
+    /// * `ResultBoxedEX` - error lists every problem
+    pub fn validate(&self) -> ResultBoxedEX {
+        let report = ConfigReport { problems: self.problems() };
+        if !report.is_empty() {
+            return Err(Erx::boxed(&report.to_string()));
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn yaml(content: &str) -> Config {
+        Config::builder().add_source(config::File::from_str(content, config::FileFormat::Yaml)).build().unwrap()
+    }
+
+    const INVALID: &str = r#"
+name: logos
+short: LOGO
+debug: false
+web:
+  api:
+    port: 8080
+    bind: localhost
+    middleware:
+      limitor:
+        priority: high
+  admin:
+    port: 8080
+model:
+  backends:
+    pg:
+      kind: Postgres
+      readonly: false
+      connect: ""
+    cache:
+      kind: Redis
+      readonly: false
+      connect: "postgres://localhost"
+log:
+  level: info
+  console: true
+  dirs: "/not/exists/logs"
+"#;
+
+    #[test]
+    fn test_report_every_problem() {
+        let report = load(&yaml(INVALID)).unwrap_err();
+        let keys: Vec<&str> = report.problems.iter().map(|p| p.key.as_str()).collect();
+        assert_eq!(
+            keys,
+            vec![
+                "log.dirs",
+                "model.backends.cache.connect",
+                "model.backends.pg.connect",
+                "web.api.bind",
+                "web.api.middleware.limitor.priority",
+                "web.api.port",
+            ]
+        );
+
+        let text = report.to_string();
+        assert!(text.starts_with("config invalid, 6 problem(s):"));
+        assert!(text.contains("log.dirs: '/not/exists/logs' is not a directory"));
+
+        assert!(binds_conflict("127.0.0.1", "0.0.0.0"));
+        assert!(!binds_conflict("127.0.0.1", "10.0.0.1"));
+    }
+
+    #[test]
+    fn test_problem_source() {
+        let local = yaml("log:\n  dirs: \"/not/exists/logs\"\n");
+        let layers = vec![ConfigLayer::new("config/config.yml", yaml(INVALID)), ConfigLayer::new("config/local.yml", local)];
+        let report = load_with(&yaml(INVALID), &layers).unwrap_err();
+
+        let source = |key: &str| report.problems.iter().find(|p| p.key == key).and_then(|p| p.source.clone());
+        assert_eq!(source("log.dirs").as_deref(), Some("config/local.yml"));
+        assert_eq!(source("web.api.port").as_deref(), Some("config/config.yml"));
+        assert!(report.to_string().contains("(from config/local.yml)"));
+    }
+
+    #[test]
+    fn test_debug_mode_accepts_problems() {
+        let rebit = load(&yaml(&INVALID.replace("debug: false", "debug: true"))).unwrap();
+        assert_eq!(rebit.problems().len(), 6);
+        assert!(rebit.validate().is_err());
+    }
+
+    #[test]
+    fn test_replica_problems() {
+        let replicas = r#"
+name: logos
+short: LOGO
+debug: false
+web: {}
+model:
+  backends:
+    main:
+      kind: Postgres
+      readonly: false
+      connect: "postgres://localhost/main"
+    main_r1:
+      kind: Postgres
+      readonly: false
+      replica_of: main
+      connect: "postgres://localhost/main"
+    lost_r1:
+      kind: Postgres
+      readonly: true
+      replica_of: lost
+      connect: "postgres://localhost/lost"
+"#;
+        let keys: Vec<String> = load(&yaml(replicas)).unwrap_err().problems.into_iter().map(|p| p.key).collect();
+        assert_eq!(keys, vec!["model.backends.lost_r1.replica_of", "model.backends.main_r1.readonly"]);
+    }
+
+    #[test]
+    fn test_pool_problems() {
+        let pool = crate::conf::Pool { max_connections: 4, min_connections: 8, acquire_timeout: 0, statement_log: "loud".to_string(), ..Default::default() };
+        let keys: Vec<String> = pool_problems("pool", &pool).into_iter().map(|p| p.key).collect();
+        assert_eq!(keys, vec!["pool.min_connections", "pool.acquire_timeout", "pool.statement_log"]);
+        assert!(pool_problems("pool", &crate::conf::Pool::default()).is_empty());
+    }
+
+    #[test]
+    fn test_deserialize_problem() {
+        let report = load(&yaml(&INVALID.replace("port: 8080\n    bind", "port: eighty\n    bind"))).unwrap_err();
+        assert_eq!(report.problems.len(), 1);
+        assert_eq!(report.problems[0].key, "web.api.port");
+    }
+}
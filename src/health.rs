@@ -0,0 +1,261 @@
+use crate::erx::ResultBoxedE;
+use crate::rings::{RingState, Rings, RingsApplication, R};
+use serde::{Deserialize, Serialize};
+use std::future::Future;
+use std::time::Duration;
+
+/// max time a single health check may take
+pub const HEALTH_CHECK_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// liveness route
+pub const HEALTHZ_PATH: &str = "/healthz";
+
+/// readiness route
+pub const READYZ_PATH: &str = "/readyz";
+
+/// Health Status, ordered from best to worst
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "lowercase")]
+pub enum HealthStatus {
+    Up,
+    Degraded,
+    Down,
+}
+
+/// what a health check is about
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum HealthKind {
+    Application,
+    Mod,
+    Service,
+    Backend,
+}
+
+/// Health Check result
+///
+/// # Fields
+///
+/// * `name` - The checked mod, service or backend name.
+/// * `kind` - What is checked.
+/// * `status` - The result.
+/// * `details` - Human readable details, e.g. the mod stage or the ping error.
+/// * `elapsed` - Micros the check took.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct HealthCheck {
+    pub name: String,
+    pub kind: HealthKind,
+    pub status: HealthStatus,
+    pub details: String,
+    pub elapsed: i64,
+}
+
+/// Health Report, status is the worst status of all checks
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct HealthReport {
+    pub app: String,
+    pub status: HealthStatus,
+    pub checks: Vec<HealthCheck>,
+    pub time: i64,
+}
+
+impl HealthReport {
+    fn of(app: &str, checks: Vec<HealthCheck>) -> Self {
+        let status = checks.iter().map(|c| c.status).max().unwrap_or(HealthStatus::Up);
+        Self { app: app.to_string(), status, checks, time: chrono::Utc::now().timestamp_micros() }
+    }
+
+    /// up or degraded
+    pub fn is_healthy(&self) -> bool {
+        self.status != HealthStatus::Down
+    }
+}
+
+fn instant(name: &str, kind: HealthKind, status: HealthStatus, details: &str) -> HealthCheck {
+    HealthCheck { name: name.to_string(), kind, status, details: details.to_string(), elapsed: 0 }
+}
+
+/// run a check within `timeout`, a timeout or an error is `Down`
+async fn timed<F>(name: &str, kind: HealthKind, timeout: Duration, check: F) -> HealthCheck
+where
+    F: Future<Output = ResultBoxedE<()>>,
+{
+    let begin = crate::rings::moment_begin();
+    let (status, details) = match tokio::time::timeout(timeout, check).await {
+        Ok(Ok(_)) => (HealthStatus::Up, "ok".to_string()),
+        Ok(Err(ex)) => (HealthStatus::Down, ex.message_string()),
+        Err(_) => (HealthStatus::Down, format!("timeout after {:?}", timeout)),
+    };
+    HealthCheck { name: name.to_string(), kind, status, details, elapsed: crate::rings::moment_begin() - begin }
+}
+
+/// mod stage as liveness: anything but failed or terminated is alive
+fn mod_liveness(stage: RingState) -> HealthStatus {
+    match stage {
+        RingState::Failed | RingState::Terminated | RingState::Unknown => HealthStatus::Down,
+        RingState::Terminating => HealthStatus::Degraded,
+        _ => HealthStatus::Up,
+    }
+}
+
+/// mod stage as readiness: only working mods serve
+fn mod_readiness(stage: RingState) -> HealthStatus {
+    match stage {
+        RingState::Working => HealthStatus::Up,
+        RingState::Paused => HealthStatus::Degraded,
+        _ => HealthStatus::Down,
+    }
+}
+
+async fn state_checks(app: &Rings, judge: fn(RingState) -> HealthStatus) -> (String, Vec<HealthCheck>) {
+    let state = app.get_state_unchecked();
+    let name = app.name().to_string();
+
+    let mut checks = vec![instant(&name, HealthKind::Application, judge(state), <RingState as Into<&str>>::into(state))];
+    let mut stages: Vec<(String, RingState)> = app.mods_stages().await.into_iter().collect();
+    stages.sort();
+    for (md, stage) in stages {
+        checks.push(instant(&md, HealthKind::Mod, judge(stage), stage.into()));
+    }
+
+    (name, checks)
+}
+
+/// report of an application held locked longer than `timeout`, e.g. across fire, restart or shutdown
+fn busy(status: HealthStatus, timeout: Duration) -> HealthReport {
+    let details = format!("busy, not readable within {:?}, firing, restarting or shutting down", timeout);
+    HealthReport::of("", vec![instant("application", HealthKind::Application, status, &details)])
+}
+
+/// liveness of a rings application, from the application and mod stages only, no I/O.
+/// an application busy past `HEALTH_CHECK_TIMEOUT` is alive but degraded
+pub async fn liveness(app: &RingsApplication) -> HealthReport {
+    let checked = tokio::time::timeout(HEALTH_CHECK_TIMEOUT, async { state_checks(&*app.read().await, mod_liveness).await }).await;
+    match checked {
+        Ok((name, checks)) => HealthReport::of(&name, checks),
+        Err(_) => busy(HealthStatus::Degraded, HEALTH_CHECK_TIMEOUT),
+    }
+}
+
+/// readiness of a rings application: mods working, services ready, and backends answering ping,
+/// reading the application, every service and backend check is bounded by `timeout`, a busy application is not ready
+pub async fn readiness(app: &RingsApplication, timeout: Duration) -> HealthReport {
+    let checked = tokio::time::timeout(timeout, async {
+        let app = app.read().await;
+        let (name, checks) = state_checks(&app, mod_readiness).await;
+        (name, checks, app.services(), app.models())
+    })
+    .await;
+    let Ok((name, mut checks, services, models)) = checked else {
+        return busy(HealthStatus::Down, timeout);
+    };
+
+    for managed in services.managed_services() {
+        let check = match managed.read() {
+            Ok(service) if service.ready() => instant(service.name(), HealthKind::Service, HealthStatus::Up, "ready"),
+            Ok(service) => instant(service.name(), HealthKind::Service, HealthStatus::Down, "not ready"),
+            Err(ex) => instant("unknown", HealthKind::Service, HealthStatus::Down, &ex.to_string()),
+        };
+        checks.push(check);
+    }
+
+    let database = async {
+        if !models.has_database() {
+            return None;
+        }
+        let mut check = timed("database", HealthKind::Backend, timeout, models.ping_database()).await;
+        if check.status == HealthStatus::Up {
+            // a saturated pool still answers, but callers queue for connections
+            let stats = models.pool_stats();
+            if stats.iter().any(|p| p.max > 0 && p.busy() >= p.max) {
+                check.status = HealthStatus::Degraded;
+            }
+            check.details = stats.iter().map(|p| format!("{} {}/{} busy", p.backend, p.busy(), p.max)).collect::<Vec<String>>().join(", ");
+        }
+        Some(check)
+    };
+    let redis = async {
+        if !models.has_redis() {
+            return None;
+        }
+        Some(timed("redis", HealthKind::Backend, timeout, models.ping_redis()).await)
+    };
+    let (database, redis) = tokio::join!(database, redis);
+    checks.extend(database.into_iter().chain(redis));
+
+    HealthReport::of(&name, checks)
+}
+
+async fn answer(app_name: String, ready: bool) -> axum::response::Response {
+    use axum::response::IntoResponse;
+
+    let app = match R::instance(app_name.clone()).await {
+        Ok(app) => app,
+        Err(ex) => {
+            let report = HealthReport::of(&app_name, vec![instant(&app_name, HealthKind::Application, HealthStatus::Down, &ex)]);
+            return (axum::http::StatusCode::SERVICE_UNAVAILABLE, axum::Json(report)).into_response();
+        },
+    };
+
+    let mut report = if ready { readiness(&app, HEALTH_CHECK_TIMEOUT).await } else { liveness(&app).await };
+    report.app = app_name;
+    let code = if report.is_healthy() { axum::http::StatusCode::OK } else { axum::http::StatusCode::SERVICE_UNAVAILABLE };
+    (code, axum::Json(report)).into_response()
+}
+
+/// `/healthz` and `/readyz` of the named rings application,
+/// answers 200 when up or degraded, 503 when down, with the report as json body.
+/// both are served while the web mod is paused: liveness does not reflect a pause,
+/// readiness reports the paused mods as degraded
+pub fn routes_of(app_name: &str) -> axum::Router {
+    let (live, ready) = (app_name.to_string(), app_name.to_string());
+    axum::Router::new()
+        .route(HEALTHZ_PATH, axum::routing::get(move || answer(live.clone(), false)))
+        .route(READYZ_PATH, axum::routing::get(move || answer(ready.clone(), true)))
+}
+
+/// `/healthz` and `/readyz` of the default rings application, mount them with the web router maker:
+/// `web_route_merge!(rings::health::routes(), ...)`
+pub fn routes() -> Vec<axum::Router> {
+    vec![routes_of(crate::rings::default_rings_name())]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stage_judges() {
+        assert_eq!(mod_liveness(RingState::Paused), HealthStatus::Up);
+        assert_eq!(mod_liveness(RingState::Failed), HealthStatus::Down);
+        assert_eq!(mod_readiness(RingState::Ready), HealthStatus::Down);
+        assert_eq!(mod_readiness(RingState::Paused), HealthStatus::Degraded);
+        assert_eq!(mod_readiness(RingState::Working), HealthStatus::Up);
+    }
+
+    #[tokio::test]
+    async fn test_report_status() {
+        let checks = vec![
+            instant("web", HealthKind::Mod, HealthStatus::Up, "working"),
+            instant("scheduler", HealthKind::Mod, HealthStatus::Degraded, "paused"),
+        ];
+        let report = HealthReport::of("test", checks);
+        assert_eq!(report.status, HealthStatus::Degraded);
+        assert!(report.is_healthy());
+
+        let slow = timed("slow", HealthKind::Backend, Duration::from_millis(10), async {
+            tokio::time::sleep(Duration::from_secs(1)).await;
+            Ok(())
+        })
+        .await;
+        assert_eq!(slow.status, HealthStatus::Down);
+        assert!(slow.details.contains("timeout"));
+
+        let report = HealthReport::of("test", vec![slow]);
+        assert!(!report.is_healthy());
+        assert!(serde_json::to_string(&report).unwrap().contains(r#""status":"down""#));
+
+        assert!(busy(HealthStatus::Degraded, HEALTH_CHECK_TIMEOUT).is_healthy());
+        assert!(!busy(HealthStatus::Down, HEALTH_CHECK_TIMEOUT).is_healthy());
+    }
+}
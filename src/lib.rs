@@ -9,6 +9,7 @@ pub mod conf;
 pub mod core;
 pub mod erx;
 pub mod fns;
+pub mod health;
 pub mod log;
 pub mod macros;
 pub mod migrate;
@@ -68,9 +68,30 @@ pub async fn logging_initialize() {
     }
 
     let directives: &str = &log_conf.level;
-    let filter = tracing_subscriber::EnvFilter::new(directives);
+    let (filter, filter_reload) = tracing_subscriber::reload::Layer::new(tracing_subscriber::EnvFilter::new(directives));
     tracing_subscriber::registry().with(console).with(persist).with(filter).init();
 
+    // follow `log.level` on config reload
+    tokio::spawn(async move {
+        let mut changes = crate::conf::subscribe();
+        loop {
+            match changes.recv().await {
+                Ok(change) if change.changed("log.level") => {},
+                Ok(_) | Err(tokio::sync::broadcast::error::RecvError::Lagged(_)) => continue,
+                Err(tokio::sync::broadcast::error::RecvError::Closed) => break,
+            }
+
+            let level = crate::conf::rebit().read().await.log.clone().unwrap_or_default().level;
+            match tracing_subscriber::EnvFilter::try_new(&level) {
+                Ok(filter) => match filter_reload.reload(filter) {
+                    Ok(_) => tracing::info!("log level reloaded: {}", level),
+                    Err(ex) => tracing::error!("log level reload failed: {}", ex),
+                },
+                Err(ex) => tracing::error!("log level '{}' invalid: {}", level, ex),
+            }
+        }
+    });
+
     unsafe {
         _LOG_WORKER_GUARD.extend(guards);
     }
@@ -2,6 +2,7 @@ pub mod dbms;
 pub mod facade;
 pub mod jq;
 pub mod prefoundation;
+pub mod router;
 pub mod rs;
 pub mod sqlgen;
 pub mod status;
@@ -18,24 +19,52 @@ use redis;
 //     Runtime as DeadPRuntime,
 // };
 
-use sea_orm::{ConnectOptions, Database, DatabaseConnection};
-use std::sync::RwLock;
+use sea_orm::{ConnectOptions, ConnectionTrait, Database, DatabaseConnection};
+use std::collections::{BTreeMap, BTreeSet};
+use std::sync::{Arc, RwLock};
 use std::time::Duration;
-use tokio::sync::OnceCell;
 use tracing::{info, span, warn};
 
 use crate::conf::{Backend, BackendKind, Dict};
+use crate::rings::Moment;
 use crate::web::url;
+use dbms::RDBMS;
+use router::{Readonly, Router};
 
 /// get shared DatabaseConnection
 /// panic if error
 pub fn shared_must() -> &'static DatabaseConnection {
-    SHARED_DB_CONNECTION.get().expect("SHARED_DB_CONNECTION get failed")
+    shared().expect("SHARED_DB_CONNECTION get failed")
 }
 
 /// get shared DatabaseConnection
+/// it's the default database connection of the default rings application, refused when the backend is readonly
 pub fn shared() -> ResultBoxedE<&'static DatabaseConnection> {
-    SHARED_DB_CONNECTION.get().ok_or(Erx::boxed("SHARED_DB_CONNECTION get failed"))
+    connections(crate::rings::default_rings_name()).database()
+}
+
+/// get the named DatabaseConnection of the default rings application, refused when the backend is readonly
+/// # Arguments
+/// * `name` - backend name, e.g. `analytics`
+pub fn db(name: &str) -> ResultBoxedE<&'static DatabaseConnection> {
+    connections(crate::rings::default_rings_name()).db(name)
+}
+
+/// get the router of the named primary database of the default rings application
+pub fn router(name: &str) -> ResultBoxedE<Arc<Router>> {
+    connections(crate::rings::default_rings_name()).router(name)
+}
+
+/// a reader of the default database, a replica when any, see `router`
+pub fn reader() -> ResultBoxedE<Readonly> {
+    connections(crate::rings::default_rings_name()).reader()
+}
+
+/// the primary of the default database, for writes and transactions
+/// # Returns
+/// * `Err` - the default database is a readonly backend
+pub fn writer() -> ResultBoxedE<&'static DatabaseConnection> {
+    connections(crate::rings::default_rings_name()).writer()
 }
 
 /// For async connections, connection pooling isn't necessary, unless blocking commands are used.
@@ -43,94 +72,406 @@ pub fn shared() -> ResultBoxedE<&'static DatabaseConnection> {
 /// For automatic reconnections consider using ConnectionManager with the connection-manager feature.
 /// Async cluster connections also don't require pooling and are thread-safe and reusable.
 pub fn make_redis_client() -> ResultBoxedE<redis::Client> {
-    let s = SHARED_REDIS_CONNECT_STRING.read().map_err(simple_conv_boxed)?.clone();
-    redis::Client::open(s).map_err(simple_conv_boxed)
+    connections(crate::rings::default_rings_name()).make_redis_client()
 }
 
-// get redis connection from pool
-// pub async fn get_redis_client() -> erx::ResultE<deadpool_redis::Connection> {
-//     let pool = SHARED_REDIS_POOL.get_or_init(|| async {
-//         deadpool_redis::Config::from_url(SHARED_REDIS_CONNECT_STRING.read().unwrap().clone())
-//             .create_pool(Some(DeadPRuntime::Tokio1))
-//             .unwrap()
-//     }).await;
-//
-//     Ok(pool.get().await.map_err(erx::smp)?)
-// }
+/// make a client of the named redis backend of the default rings application
+/// # Arguments
+/// * `name` - backend name, e.g. `cache`
+pub fn redis_client(name: &str) -> ResultBoxedE<redis::Client> {
+    connections(crate::rings::default_rings_name()).redis_client(name)
+}
 
-/// initialize model connection
-/// call once when application initialized
-pub async fn initialize_model_connection(backends: Dict<Backend>) {
-    let span = span!(tracing::Level::INFO, "INITIALIZE MODEL");
-    let _guard = span.enter();
+/// Model connections owned by a rings application
+/// # Fields
+/// * `app` - rings application name
+/// * `databases` - database connections, keyed by backend name
+/// * `default_database` - backend name of the default database
+/// * `readonly` - names of the readonly databases
+/// * `routers` - read/write routers, keyed by primary backend name
+/// * `redis_connect_strings` - redis connection strings, keyed by backend name
+/// * `default_redis` - backend name of the default redis
+/// * `redis_namespaces` - key namespaces of the redis backends, keyed by backend name, see `facade::redis::namespace`
+pub struct Connections {
+    app: String,
+    /// connections live as long as the process, same as the rings application owning them
+    databases: RwLock<BTreeMap<String, &'static DatabaseConnection>>,
+    default_database: RwLock<Option<String>>,
+    readonly: RwLock<BTreeSet<String>>,
+    routers: RwLock<BTreeMap<String, Arc<Router>>>,
+    /// can be changed by code, for example, when config changed
+    /// if changed, please call make_redis_client() to get new client
+    redis_connect_strings: RwLock<BTreeMap<String, String>>,
+    default_redis: RwLock<Option<String>>,
+    redis_namespaces: RwLock<BTreeMap<String, String>>,
+}
 
-    if backends.is_empty() {
-        warn!("No backends configured, pass init_model.");
-        return;
+/// Pool Statistics
+/// # Fields
+/// * `backend` - backend name
+/// * `size` - open connections, idle ones included
+/// * `idle` - idle connections
+/// * `max` - max connections
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct PoolStats {
+    pub backend: String,
+    pub size: u32,
+    pub idle: u32,
+    pub max: u32,
+}
+
+impl PoolStats {
+    fn of(backend: &str, db: &DatabaseConnection) -> Self {
+        let (size, idle, max) = match db.get_database_backend() {
+            sea_orm::DbBackend::Postgres => {
+                let pool = db.get_postgres_connection_pool();
+                (pool.size(), pool.num_idle() as u32, pool.options().get_max_connections())
+            },
+            sea_orm::DbBackend::Sqlite => {
+                let pool = db.get_sqlite_connection_pool();
+                (pool.size(), pool.num_idle() as u32, pool.options().get_max_connections())
+            },
+            sea_orm::DbBackend::MySql => {
+                let pool = db.get_mysql_connection_pool();
+                (pool.size(), pool.num_idle() as u32, pool.options().get_max_connections())
+            },
+        };
+        PoolStats { backend: backend.to_string(), size, idle, max }
     }
 
-    async fn postgre(backend: Backend) {
-        info!("Connecting to postgres: {:?}", backend.connect);
-        SHARED_DB_CONNECTION.get_or_init(|| async { new_database_connection(backend).await }).await;
+    /// connections in use
+    pub fn busy(&self) -> u32 {
+        self.size.saturating_sub(self.idle)
     }
+}
 
-    async fn redis(backend: Backend) {
-        let connect_string = backend.connect.clone();
+/// statistics of every database pool of the default rings application
+pub fn pool_stats() -> Vec<PoolStats> {
+    connections(crate::rings::default_rings_name()).pool_stats()
+}
+
+/// model connections, one per rings application
+/// entries live as long as the process, same as the rings applications owning them
+static CONNECTIONS: RwLock<Vec<&'static Connections>> = RwLock::new(Vec::new());
+
+/// get the model connections of the named rings application, make it if not exists
+pub fn connections(app: &str) -> &'static Connections {
+    if let Some(found) = CONNECTIONS.read().expect("model connections lock poisoned").iter().find(|c| c.app.eq(app)) {
+        return found;
+    }
+
+    let mut all = CONNECTIONS.write().expect("model connections lock poisoned");
+    if let Some(found) = all.iter().find(|c| c.app.eq(app)) {
+        return found;
+    }
+
+    let made: &'static Connections = Box::leak(Box::new(Connections {
+        app: app.to_string(),
+        databases: RwLock::new(BTreeMap::new()),
+        default_database: RwLock::new(None),
+        readonly: RwLock::new(BTreeSet::new()),
+        routers: RwLock::new(BTreeMap::new()),
+        redis_connect_strings: RwLock::new(BTreeMap::new()),
+        default_redis: RwLock::new(None),
+        redis_namespaces: RwLock::new(BTreeMap::new()),
+    }));
+    all.push(made);
+    made
+}
 
-        let mut conn = SHARED_REDIS_CONNECT_STRING.write().unwrap();
-        *conn = connect_string.clone();
+impl Connections {
+    /// rings application name
+    pub fn app(&self) -> &str {
+        &self.app
+    }
 
-        info!("Connecting to redis: {:?}", connect_string);
-        let cli = redis::Client::open(connect_string.as_str()).expect("Redis connection failed.");
-        info!("Connected to redis: {:?}", cli.get_connection_info());
+    /// get the default DatabaseConnection
+    pub fn database(&self) -> ResultBoxedE<&'static DatabaseConnection> {
+        self.db(&self.default_database_name()?)
     }
 
-    for (backend_name, backend) in backends {
-        if backend.connect.is_empty() {
-            warn!("Backend '{}' connect string is empty, pass", backend_name);
-            continue;
+    /// get the named DatabaseConnection
+    /// # Arguments
+    /// * `name` - backend name
+    /// # Returns
+    /// * `Err` - the backend is readonly, read it with `readonly`
+    pub fn db(&self, name: &str) -> ResultBoxedE<&'static DatabaseConnection> {
+        if self.readonly.read().is_ok_and(|r| r.contains(name)) {
+            return Err(router::readonly_error(name));
         }
+        self.connection(name)
+    }
+
+    /// the named database, statements other than reads are rejected
+    /// # Arguments
+    /// * `name` - backend name
+    pub fn readonly(&self, name: &str) -> ResultBoxedE<Readonly> {
+        Ok(Readonly::new(name, self.connection(name)?))
+    }
+
+    /// the named connection, readonly or not
+    fn connection(&self, name: &str) -> ResultBoxedE<&'static DatabaseConnection> {
+        let databases = self.databases.read().map_err(simple_conv_boxed)?;
+        databases.get(name).copied().ok_or(Erx::boxed(&format!("rings application '{}' database '{}' connection get failed", self.app, name)))
+    }
+
+    /// get the router of the named primary database
+    pub fn router(&self, name: &str) -> ResultBoxedE<Arc<Router>> {
+        let routers = self.routers.read().map_err(simple_conv_boxed)?;
+        routers.get(name).cloned().ok_or(Erx::boxed(&format!("rings application '{}' has no database router '{}'", self.app, name)))
+    }
+
+    /// a reader of the default database
+    pub fn reader(&self) -> ResultBoxedE<Readonly> {
+        Ok(self.router(&self.default_database_name()?)?.reader())
+    }
+
+    /// the primary of the default database
+    pub fn writer(&self) -> ResultBoxedE<&'static DatabaseConnection> {
+        self.router(&self.default_database_name()?)?.writer()
+    }
+
+    fn default_database_name(&self) -> ResultBoxedE<String> {
+        let name = self.default_database.read().map_err(simple_conv_boxed)?.clone();
+        name.ok_or(Erx::boxed(&format!("rings application '{}' has no database connection", self.app)))
+    }
+
+    /// sql dialect of the named database, for the json helpers of `model::jq`
+    pub fn dialect(&self, name: &str) -> ResultBoxedE<RDBMS> {
+        Ok(RDBMS::from(self.connection(name)?.get_database_backend()))
+    }
+
+    /// names of the connected databases
+    pub fn database_names(&self) -> Vec<String> {
+        self.databases.read().map(|d| d.keys().cloned().collect()).unwrap_or_default()
+    }
 
-        match backend.kind {
-            BackendKind::Redis => redis(backend).await,
-            BackendKind::Postgres => postgre(backend).await,
+    /// make redis client of the default redis backend
+    pub fn make_redis_client(&self) -> ResultBoxedE<redis::Client> {
+        self.redis_client(&self.default_redis_name()?)
+    }
+
+    /// backend name of the default redis
+    pub fn default_redis_name(&self) -> ResultBoxedE<String> {
+        let name = self.default_redis.read().map_err(simple_conv_boxed)?.clone();
+        name.ok_or(Erx::boxed(&format!("rings application '{}' has no redis backend", self.app)))
+    }
+
+    /// make redis client of the named redis backend, with its current connection string
+    /// # Arguments
+    /// * `name` - backend name
+    pub fn redis_client(&self, name: &str) -> ResultBoxedE<redis::Client> {
+        let s = self.redis_connect_strings.read().map_err(simple_conv_boxed)?.get(name).cloned();
+        let s = s.ok_or(Erx::boxed(&format!("rings application '{}' redis '{}' is not configured", self.app, name)))?;
+        redis::Client::open(s).map_err(simple_conv_boxed)
+    }
+
+    /// key namespace of the named redis backend, empty when keys are not namespaced
+    /// # Arguments
+    /// * `name` - backend name
+    pub fn redis_namespace(&self, name: &str) -> String {
+        self.redis_namespaces.read().ok().and_then(|n| n.get(name).cloned()).unwrap_or_default()
+    }
+
+    /// names of the configured redis backends
+    pub fn redis_names(&self) -> Vec<String> {
+        self.redis_connect_strings.read().map(|r| r.keys().cloned().collect()).unwrap_or_default()
+    }
+
+    /// statistics of every database pool, sorted by backend name
+    pub fn pool_stats(&self) -> Vec<PoolStats> {
+        self.database_names().into_iter().filter_map(|name| self.connection(&name).ok().map(|db| PoolStats::of(&name, db))).collect()
+    }
+
+    /// any database connection is initialized
+    pub fn has_database(&self) -> bool {
+        self.databases.read().is_ok_and(|d| !d.is_empty())
+    }
+
+    /// any redis connection string is configured
+    pub fn has_redis(&self) -> bool {
+        self.redis_connect_strings.read().is_ok_and(|r| !r.is_empty())
+    }
+
+    /// ping every database, the first failure reported
+    pub async fn ping_database(&self) -> ResultBoxedE<()> {
+        for name in self.database_names() {
+            self.connection(&name)?.ping().await.map_err(|ex| Erx::boxed(&format!("database '{}': {}", name, ex)))?;
         }
+        Ok(())
+    }
+
+    /// PING every redis, with a fresh multiplexed connection, the first failure reported
+    pub async fn ping_redis(&self) -> ResultBoxedE<()> {
+        for name in self.redis_names() {
+            let ping = async {
+                let mut conn = self.redis_client(&name)?.get_multiplexed_async_connection().await.map_err(simple_conv_boxed)?;
+                let pong: String = redis::cmd("PING").query_async(&mut conn).await.map_err(simple_conv_boxed)?;
+                let pinged: ResultBoxedE<()> = match pong.as_str() {
+                    "PONG" => Ok(()),
+                    _ => Err(Erx::boxed(&format!("unexpected redis PING reply: {}", pong))),
+                };
+                pinged
+            };
+            ping.await.map_err(|ex| Erx::boxed(&format!("redis '{}': {}", name, ex.message())))?;
+        }
+        Ok(())
+    }
+
+    /// initialize model connection
+    /// call once when application initialized
+    /// connect every backend, returns the connect moment of each backend
+    pub async fn initialize(&self, backends: Dict<Backend>) -> Vec<Moment> {
+        let span = span!(tracing::Level::INFO, "INITIALIZE MODEL", app = self.app.as_str());
+        let _guard = span.enter();
+
+        let mut moments = vec![];
+        if backends.is_empty() {
+            warn!("No backends configured, pass init_model.");
+            return moments;
+        }
+
+        let model = crate::conf::Model { backends: Some(backends.clone()) };
+        let namespace = facade::redis::namespace::app_namespace().await;
+        let mut backends: Vec<(String, Backend)> = backends.into_iter().collect();
+        backends.sort_by(|a, b| a.0.cmp(&b.0));
+
+        for (backend_name, backend) in backends {
+            if backend.connect.is_empty() {
+                warn!("Backend '{}' connect string is empty, pass", backend_name);
+                continue;
+            }
+
+            let begin = crate::rings::moment_begin();
+            match backend.kind {
+                BackendKind::Redis => self.redis(&backend_name, backend, &namespace),
+                BackendKind::Postgres | BackendKind::Sqlite | BackendKind::MySQL => self.connect_database(&backend_name, backend).await,
+            }
+            moments.push(Moment::since(&format!("model backend [{}] connect", backend_name), begin));
+        }
+
+        if let Ok(mut default) = self.default_database.write() {
+            *default = model.default_name(true).filter(|name| self.databases.read().is_ok_and(|d| d.contains_key(name)));
+        }
+        if let Ok(mut default) = self.default_redis.write() {
+            *default = model.default_name(false).filter(|name| self.redis_connect_strings.read().is_ok_and(|r| r.contains_key(name)));
+        }
+        self.route(&model);
+
+        moments
+    }
+
+    /// make a router for every connected primary, with its connected replicas
+    fn route(&self, model: &crate::conf::Model) {
+        let mut routers = BTreeMap::new();
+        for name in self.database_names() {
+            let (Some(backend), Ok(connection)) = (model.backend(&name), self.connection(&name)) else {
+                continue;
+            };
+            if backend.replica_of.is_some() {
+                continue;
+            }
+
+            let replicas: Vec<(String, &'static DatabaseConnection, u8)> =
+                model.replicas_of(&name).into_iter().filter_map(|(n, b)| self.connection(&n).ok().map(|c| (n, c, b.weight.unwrap_or(1)))).collect();
+            let router = Router::new(&name, backend.readonly, connection, replicas);
+            info!("Database router [{}]: reads go to {:?}", name, router.reader_names());
+            routers.insert(name, Arc::new(router));
+        }
+
+        if let Ok(mut current) = self.routers.write() {
+            *current = routers;
+        }
+    }
+
+    async fn connect_database(&self, name: &str, mut backend: Backend) {
+        if self.databases.read().is_ok_and(|d| d.contains_key(name)) {
+            warn!("Database '{}' connected already, pass", name);
+            return;
+        }
+
+        info!("Connecting to {} [{}]: {:?}", backend.kind, name, crate::conf::secret::redact(&backend.connect));
+        if backend.readonly {
+            if let Ok(mut readonly) = self.readonly.write() {
+                readonly.insert(name.to_string());
+            }
+        }
+        backend.pool.application_name.get_or_insert(self.app.clone());
+        let connection: &'static DatabaseConnection = Box::leak(Box::new(new_database_connection(backend).await));
+        if let Ok(mut databases) = self.databases.write() {
+            databases.insert(name.to_string(), connection);
+        }
+    }
+
+    fn redis(&self, name: &str, backend: Backend, app_namespace: &str) {
+        let connect_string = backend.connect.clone();
+
+        if let Ok(mut namespaces) = self.redis_namespaces.write() {
+            namespaces.insert(name.to_string(), backend.namespace.clone().unwrap_or(app_namespace.to_string()));
+        }
+
+        let mut conn = self.redis_connect_strings.write().unwrap();
+        conn.insert(name.to_string(), connect_string.clone());
+
+        info!("Connecting to redis [{}]: {:?}", name, crate::conf::secret::redact(&connect_string));
+        redis::Client::open(connect_string.as_str()).expect("Redis connection failed.");
+        info!("Connected to redis [{}]: {:?}", name, crate::conf::secret::redact(&connect_string));
     }
 }
 
+// get redis connection from pool
+// pub async fn get_redis_client() -> erx::ResultE<deadpool_redis::Connection> {
+//     let pool = SHARED_REDIS_POOL.get_or_init(|| async {
+//         deadpool_redis::Config::from_url(SHARED_REDIS_CONNECT_STRING.read().unwrap().clone())
+//             .create_pool(Some(DeadPRuntime::Tokio1))
+//             .unwrap()
+//     }).await;
+//
+//     Ok(pool.get().await.map_err(erx::smp)?)
+// }
+
+/// initialize model connection
+/// call once when application initialized
+/// the connections belong to the default rings application
+pub async fn initialize_model_connection(backends: Dict<Backend>) {
+    connections(crate::rings::default_rings_name()).initialize(backends).await;
+}
+
 /// new database connection
 pub async fn new_database_connection(backend: Backend) -> DatabaseConnection {
     match backend.kind {
         BackendKind::Redis => {
             panic!("Redis Backend use new_database_connection is not supported yet");
         },
-        BackendKind::Postgres => (),
+        BackendKind::Postgres | BackendKind::Sqlite | BackendKind::MySQL => (),
     };
 
-    const MAX_CONNECTIONS: u32 = 100;
-    const MIN_CONNECTIONS: u32 = 2;
-    const CONNECT_TIMEOUT: Duration = Duration::from_secs(8);
-    const ACQUIRE_TIMEOUT: Duration = Duration::from_secs(8);
-    const IDLE_TIMEOUT: Duration = Duration::from_secs(60 * 5);
-    const MAX_LIFETIME: Duration = Duration::from_secs(60 * 60);
-
+    let pool = &backend.pool;
     let connection_string = backend.connect.clone();
     let mut opt = ConnectOptions::new(connection_string.clone());
 
-    use log;
-
-    opt.max_connections(MAX_CONNECTIONS)
-        .min_connections(MIN_CONNECTIONS)
-        .connect_timeout(CONNECT_TIMEOUT)
-        .acquire_timeout(ACQUIRE_TIMEOUT)
-        .idle_timeout(IDLE_TIMEOUT)
-        .max_lifetime(MAX_LIFETIME)
-        .connect_lazy(false)
-        .sqlx_logging(true)
-        .sqlx_logging_level(log::LevelFilter::Info)
-        .sqlx_logging(true)
-        .sqlx_logging_level(log::LevelFilter::Info)
-        .sqlx_slow_statements_logging_settings(log::LevelFilter::Warn, Duration::from_secs(2));
+    opt.max_connections(pool.max_connections)
+        .min_connections(pool.min_connections)
+        .connect_timeout(Duration::from_secs(pool.connect_timeout))
+        .acquire_timeout(Duration::from_secs(pool.acquire_timeout))
+        .idle_timeout(Duration::from_secs(pool.idle_timeout))
+        .max_lifetime(Duration::from_secs(pool.max_lifetime))
+        .connect_lazy(pool.lazy);
+
+    match pool.statement_log_level() {
+        Some(log::LevelFilter::Off) => {
+            opt.sqlx_logging(false);
+        },
+        level => {
+            opt.sqlx_logging(true)
+                .sqlx_logging_level(level.unwrap_or(log::LevelFilter::Info))
+                .sqlx_slow_statements_logging_settings(log::LevelFilter::Warn, Duration::from_millis(pool.slow_threshold));
+        },
+    }
+
+    if let (BackendKind::Postgres, Some(application_name)) = (&backend.kind, pool.application_name.clone()) {
+        opt.map_sqlx_postgres_opts(move |pg| pg.application_name(&application_name));
+    }
 
     if backend.kind == BackendKind::Postgres && connection_string.contains("currentSchema") {
         let params = url::parse_url_query(connection_string.as_str());
@@ -143,12 +484,60 @@ pub async fn new_database_connection(backend: Backend) -> DatabaseConnection {
     Database::connect(opt).await.expect("Database connection failed")
 }
 
-/// shared database connection
-static SHARED_DB_CONNECTION: OnceCell<DatabaseConnection> = OnceCell::const_new();
+// static SHARED_REDIS_POOL: OnceCell<deadpool_redis::Pool> = OnceCell::const_new();
 
-/// shared redis connection string
-/// can be changed by code, for example, when config changed
-/// if changed, please call make_redis_client() to get new client
-static SHARED_REDIS_CONNECT_STRING: RwLock<String> = RwLock::new(String::new());
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sea_orm::Statement;
 
-// static SHARED_REDIS_POOL: OnceCell<deadpool_redis::Pool> = OnceCell::const_new();
+    fn sqlite(file: &std::path::Path, readonly: bool, replica_of: Option<&str>) -> Backend {
+        Backend {
+            kind: BackendKind::Sqlite,
+            readonly,
+            connect: format!("sqlite://{}?mode=rwc", file.display()),
+            default: false,
+            replica_of: replica_of.map(String::from),
+            weight: None,
+            pool: Default::default(),
+            namespace: None,
+            options: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_sqlite_backend() {
+        let file = std::env::temp_dir().join(format!("rings_model_test_{}.db", std::process::id()));
+        let mut backends = Dict::new();
+        backends.insert("main".to_string(), sqlite(&file, false, None));
+        backends.insert("main_r1".to_string(), sqlite(&file, true, Some("main")));
+
+        let models = connections("model-sqlite-test");
+        models.initialize(backends).await;
+        assert_eq!(models.database_names(), vec!["main", "main_r1"]);
+        assert_eq!(models.dialect("main").unwrap(), RDBMS::SQLite);
+
+        let writer = models.writer().unwrap();
+        writer.execute_unprepared("CREATE TABLE IF NOT EXISTS rings (id INTEGER PRIMARY KEY, name TEXT)").await.unwrap();
+        writer.execute_unprepared("INSERT INTO rings (name) VALUES ('logos')").await.unwrap();
+
+        let reader = models.reader().unwrap();
+        assert_eq!(reader.name(), "main_r1");
+        let found = reader.query_one(Statement::from_string(reader.get_database_backend(), "SELECT name FROM rings")).await.unwrap().unwrap();
+        assert_eq!(found.try_get::<String>("", "name").unwrap(), "logos");
+
+        let rejected = reader.query_one(Statement::from_string(reader.get_database_backend(), "INSERT INTO rings (name) VALUES ('x') RETURNING id")).await;
+        assert!(rejected.unwrap_err().to_string().contains("'main_r1' is readonly"));
+        let refused = models.db("main_r1").unwrap_err();
+        assert_eq!(refused.code().get_category(), router::READONLY_CATEGORY);
+        assert!(models.db("main").is_ok());
+        let rejected = models.readonly("main").unwrap().execute_unprepared("DELETE FROM rings").await.unwrap_err();
+        assert_eq!(router::db_erx(rejected).code().get_category(), router::READONLY_CATEGORY);
+
+        let stats = models.pool_stats();
+        assert_eq!(stats.len(), 2);
+        assert_eq!(stats[0].max, 100);
+
+        let _ = std::fs::remove_file(file);
+    }
+}
@@ -13,6 +13,16 @@ pub enum RDBMS {
     SQLite,
 }
 
+impl From<sea_orm::DbBackend> for RDBMS {
+    fn from(backend: sea_orm::DbBackend) -> Self {
+        match backend {
+            sea_orm::DbBackend::Postgres => RDBMS::Postgres,
+            sea_orm::DbBackend::MySql => RDBMS::MySQL,
+            sea_orm::DbBackend::Sqlite => RDBMS::SQLite,
+        }
+    }
+}
+
 /// 数据库连接信息
 #[derive(Debug, Deserialize, Serialize, Clone, Default)]
 pub struct ConnectBasic {
@@ -1,39 +1,33 @@
-use crate::erx;
-use redis::{Commands, FromRedisValue, ToRedisArgs};
-use std::fmt::Display;
+//! Redis facade
+//!
+//! `Redis` is async, commands share one multiplexed connection which is made again after it broke,
+//! the blocking ones, e.g. `blpop`, run on a connection of their own.
+//! `blocking::BlockingRedis` has the same methods for sync callers, a connection per command.
+//! keys are in the namespace of the backend, see `namespace`.
+//! `pubsub` publishes and subscribes typed messages, `stream` adds entries and reads them by consumer groups.
+//! ```ignore
+//! let redis = rings::model::facade::redis::Redis::shared()?;
+//! redis.set_ex("XR:token", "value", 60).await?;
+//! ```
 
-#[allow(dead_code)]
+use crate::erx;
+use redis::aio::MultiplexedConnection;
+use redis::{AsyncCommands, FromRedisValue, ToRedisArgs};
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex, RwLock};
+
+/// Async Redis facade, cheap to clone, clones share the connection
+/// # Fields
+/// * `trace` - log command errors
+/// * `client` - redis client
+/// * `connection` - the shared multiplexed connection, made on first use and after broken
+/// * `prefix` - key prefix of the namespace, see `namespace`
+#[derive(Clone)]
 pub struct Redis {
     trace: bool,
     client: redis::Client,
-}
-
-#[allow(unused)]
-macro_rules! conn_mut {
-    ($i:expr, $s:expr) => {
-        match $i.client.get_connection() {
-            Ok(c) => c,
-            Err(e) => {
-                if $i.trace {
-                    tracing::error!("RedisClient::get_connection {}", e);
-                }
-                return $s;
-            },
-        }
-    };
-}
-
-#[allow(unused)]
-fn unwra<T, E>(trace: bool, value: T) -> impl FnOnce(E) -> T
-where
-    E: Display,
-{
-    move |e: E| {
-        if trace {
-            tracing::error!("Redis error: {}", e);
-        }
-        value
-    }
+    connection: Arc<Mutex<Option<MultiplexedConnection>>>,
+    prefix: Option<String>,
 }
 
 pub type Facade<T> = erx::ResultBoxedE<T>;
@@ -83,276 +77,323 @@ pub mod rs {
     }
 }
 
-macro_rules! redis_c {
+macro_rules! redis_a {
     // 基本形式：方法名、额外参数（不包括 key）、返回类型
-    ($method_name:ident, ($($arg_name:ident: $arg_type:ty $(=> $transform:expr)?),*), $return_type:ty) => {
-        pub fn $method_name<K: ToRedisArgs>(&self, key: K, $($arg_name: $arg_type),*) -> $return_type {
-            self.get_connection()?.$method_name(key, $(redis_c!(@process_arg $arg_name $($transform)?)),*).map_err(erx::simple_conv_boxed)
+    ($method_name:ident, ($($arg_name:ident: $arg_type:ty $(as $role:ident)? $(=> $transform:expr)?),*), $return_type:ty) => {
+        pub async fn $method_name<K: ToRedisArgs + Send + Sync>(&self, key: K, $($arg_name: $arg_type),*) -> $return_type {
+            let mut conn = self.connection_for(stringify!($method_name)).await?;
+            self.settle(conn.$method_name(self.key(key), $(redis_a!(@process_arg self $arg_name $($role)? $($transform)?)),*).await)
         }
     };
 
     // 支持额外泛型参数
-    ($method_name:ident, ($($arg_name:ident: $arg_type:ty $(=> $transform:expr)?),*), $return_type:ty, generics: [$($generic:tt)*]) => {
-        pub fn $method_name<K: ToRedisArgs, $($generic)*>(&self, key: K, $($arg_name: $arg_type),*) -> $return_type {
-            self.get_connection()?.$method_name(key, $(redis_c!(@process_arg $arg_name $($transform)?)),*).map_err(erx::simple_conv_boxed)
+    ($method_name:ident, ($($arg_name:ident: $arg_type:ty $(as $role:ident)? $(=> $transform:expr)?),*), $return_type:ty, generics: [$($generic:tt)*]) => {
+        pub async fn $method_name<K: ToRedisArgs + Send + Sync, $($generic)*>(&self, key: K, $($arg_name: $arg_type),*) -> $return_type {
+            let mut conn = self.connection_for(stringify!($method_name)).await?;
+            self.settle(conn.$method_name(self.key(key), $(redis_a!(@process_arg self $arg_name $($role)? $($transform)?)),*).await)
         }
     };
 
     // 支持 no_key：不添加 key: K
-    ($method_name:ident, no_key, ($($arg_name:ident: $arg_type:ty $(=> $transform:expr)?),*), $return_type:ty) => {
-        pub fn $method_name(&self, $($arg_name: $arg_type),*) -> $return_type {
-            self.get_connection()?.$method_name($(redis_c!(@process_arg $arg_name $($transform)?)),*).map_err(erx::simple_conv_boxed)
+    ($method_name:ident, no_key, ($($arg_name:ident: $arg_type:ty $(as $role:ident)? $(=> $transform:expr)?),*), $return_type:ty) => {
+        pub async fn $method_name(&self, $($arg_name: $arg_type),*) -> $return_type {
+            let mut conn = self.connection_for(stringify!($method_name)).await?;
+            self.settle(conn.$method_name($(redis_a!(@process_arg self $arg_name $($role)? $($transform)?)),*).await)
         }
     };
 
     // 支持 no_key 和 generics
-    ($method_name:ident, no_key, ($($arg_name:ident: $arg_type:ty $(=> $transform:expr)?),*), $return_type:ty, generics: [$($generic:tt)*]) => {
-        pub fn $method_name<$($generic)*>(&self, $($arg_name: $arg_type),*) -> $return_type {
-            self.get_connection()?.$method_name($(redis_c!(@process_arg $arg_name $($transform)?)),*).map_err(erx::simple_conv_boxed)
+    ($method_name:ident, no_key, ($($arg_name:ident: $arg_type:ty $(as $role:ident)? $(=> $transform:expr)?),*), $return_type:ty, generics: [$($generic:tt)*]) => {
+        pub async fn $method_name<$($generic)*>(&self, $($arg_name: $arg_type),*) -> $return_type {
+            let mut conn = self.connection_for(stringify!($method_name)).await?;
+            self.settle(conn.$method_name($(redis_a!(@process_arg self $arg_name $($role)? $($transform)?)),*).await)
         }
     };
 
     // 支持显式指定 Redis 方法名
-    ($method_name:ident, redis: $redis_method:ident, ($($arg_name:ident: $arg_type:ty $(=> $transform:expr)?),*), $return_type:ty) => {
-        pub fn $method_name<K: ToRedisArgs>(&self, key: K, $($arg_name: $arg_type),*) -> $return_type {
-            self.get_connection()?.$redis_method(key, $(redis_c!(@process_arg $arg_name $($transform)?)),*).map_err(erx::simple_conv_boxed)
+    ($method_name:ident, redis: $redis_method:ident, ($($arg_name:ident: $arg_type:ty $(as $role:ident)? $(=> $transform:expr)?),*), $return_type:ty) => {
+        pub async fn $method_name<K: ToRedisArgs + Send + Sync>(&self, key: K, $($arg_name: $arg_type),*) -> $return_type {
+            let mut conn = self.connection_for(stringify!($method_name)).await?;
+            self.settle(conn.$redis_method(self.key(key), $(redis_a!(@process_arg self $arg_name $($role)? $($transform)?)),*).await)
         }
     };
 
     // 支持显式指定 Redis 方法名和 generics
-    ($method_name:ident, redis: $redis_method:ident, ($($arg_name:ident: $arg_type:ty $(=> $transform:expr)?),*), $return_type:ty, generics: [$($generic:tt)*]) => {
-        pub fn $method_name<K: ToRedisArgs, $($generic)*>(&self, key: K, $($arg_name: $arg_type),*) -> $return_type {
-            self.get_connection()?.$redis_method(key, $(redis_c!(@process_arg $arg_name $($transform)?)),*).map_err(erx::simple_conv_boxed)
+    ($method_name:ident, redis: $redis_method:ident, ($($arg_name:ident: $arg_type:ty $(as $role:ident)? $(=> $transform:expr)?),*), $return_type:ty, generics: [$($generic:tt)*]) => {
+        pub async fn $method_name<K: ToRedisArgs + Send + Sync, $($generic)*>(&self, key: K, $($arg_name: $arg_type),*) -> $return_type {
+            let mut conn = self.connection_for(stringify!($method_name)).await?;
+            self.settle(conn.$redis_method(self.key(key), $(redis_a!(@process_arg self $arg_name $($role)? $($transform)?)),*).await)
         }
     };
 
-    // 辅助宏：处理单个参数，决定是使用转换表达式还是原始参数名
-    (@process_arg $arg_name:ident $transform:expr) => { $transform };
-    (@process_arg $arg_name:ident) => { $arg_name };
+    // 辅助宏：处理单个参数，决定是使用转换表达式还是原始参数名，key 参数加上命名空间
+    (@process_arg $s:tt $arg_name:ident key) => { $s.key($arg_name) };
+    (@process_arg $s:tt $arg_name:ident key_pairs) => { &$s.key_pairs($arg_name) };
+    (@process_arg $s:tt $arg_name:ident $transform:expr) => { $transform };
+    (@process_arg $s:tt $arg_name:ident) => { $arg_name };
 }
 
-macro_rules! redis_i {
-    ($($method_name:ident),*) => {
-        $(
-            redis_c!($method_name, (), FacadeInt);
-        )*
+/// the command methods of both facades, `$m` makes one method of each line
+macro_rules! redis_commands {
+    ($m:ident) => {
+        $m!(exists, (), FacadeBool);
+        $m!(del, (), FacadeBool);
+        $m!(unlink, (), FacadeBool);
+        $m!(persist, (), FacadeBool);
+        $m!(ttl, (), FacadeInt);
+        $m!(expire_time, (), FacadeInt);
+        $m!(strlen, (), FacadeInt);
+
+        $m!(expire, (seconds: i64), FacadeBool);
+        $m!(expire_at, (expire_at: i64), FacadeBool);
+        $m!(rename, (new_key: N as key), FacadeBool, generics: [N: ToRedisArgs + Send + Sync]);
+        $m!(rename_nx, (new_key: N as key), FacadeBool, generics: [N: ToRedisArgs + Send + Sync]);
+        $m!(get, (), Facade<RV>, generics: [RV: FromRedisValue]);
+        $m!(mget, no_key, (key: &[K] as key), Facade<RV>, generics: [K:ToRedisArgs + Send + Sync, RV: FromRedisValue]);
+        $m!(get_ex, (expire_at: u64 => redis::Expiry::EX(expire_at)), Facade<RV>, generics: [RV: FromRedisValue]);
+        $m!(getset,(val:V), Facade<RV>, generics: [V: ToRedisArgs + Send + Sync, RV: FromRedisValue]);
+        $m!(getdel, redis: get_del, (), Facade<RV>,  generics: [RV: FromRedisValue]);
+        $m!(get_del, (), Facade<RV>, generics:[RV: FromRedisValue]);
+        $m!(getrange, (from:isize, to:isize), Facade<RV>, generics: [RV: FromRedisValue]);
+        $m!(append, (val: V), FacadeBool, generics: [V: ToRedisArgs + Send + Sync]);
+        $m!(set, (val: V), FacadeBool, generics: [V: ToRedisArgs + Send + Sync]);
+        $m!(mset, no_key, (items: &[(K, V)] as key_pairs), FacadeBool, generics: [K:ToRedisArgs + Send + Sync, V: ToRedisArgs + Send + Sync]);
+        // $m!(set_multiple, no_key, (items: &[(K, V)] as key_pairs), FacadeBool, generics: [K: ToRedisArgs + Send + Sync, V: ToRedisArgs + Send + Sync]);
+        $m!(set_options, (value:V, options:redis::SetOptions), FacadeBool, generics: [V: ToRedisArgs + Send + Sync]);
+        $m!(set_ex, (val: V,  seconds: u64), FacadeBool, generics: [V: ToRedisArgs + Send + Sync]);
+        $m!(set_nx, (val: V), FacadeBool, generics: [V: ToRedisArgs + Send + Sync]);
+        $m!(setrange, (offset:isize, value:V), FacadeBool, generics: [V: ToRedisArgs + Send + Sync]);
+        $m!(mset_nx, no_key, (items: &[(K, V)] as key_pairs),FacadeInt, generics: [K:ToRedisArgs + Send + Sync, V: ToRedisArgs + Send + Sync]);
+        $m!(incr, (delta: D), Facade<V>, generics: [D: ToRedisArgs + Send + Sync , V: FromRedisValue]);
+        $m!(decr, (delta: D), Facade<V>, generics: [D: ToRedisArgs + Send + Sync , V: FromRedisValue]);
+
+        //hash
+        $m!(hexists, (field: F), FacadeBool, generics: [F: ToRedisArgs + Send + Sync]);
+        $m!(hget, (field: F), Facade<RV>, generics: [F: ToRedisArgs + Send + Sync, RV: FromRedisValue]);
+
+        $m!(hget_ex, (fields: F, expire_at: u64 => {
+            redis::Expiry::EX(expire_at)
+        }), Facade<RV>, generics: [F: ToRedisArgs + Send + Sync, RV: FromRedisValue]);
+
+        $m!(hgetall, ( ), Facade<RV>, generics: [RV: FromRedisValue]);
+        $m!(hget_del, ( field: F), Facade<RV>, generics: [F: ToRedisArgs + Send + Sync, RV: FromRedisValue]);
+        $m!(hset, ( field: F, val: V), FacadeBool, generics: [F: ToRedisArgs + Send + Sync, V: ToRedisArgs + Send + Sync]);
+
+        $m!(hset_ex, (
+                expire_at: u64 => &{
+                    redis::HashFieldExpirationOptions::default()
+                    .set_expiration(
+                        redis::SetExpiry::EX(expire_at)
+                    )
+                },
+                values: &[(F, V)]
+            ), FacadeBool, generics: [F: ToRedisArgs + Send + Sync, V: ToRedisArgs + Send + Sync]);
+
+        $m!(hset_nx, (field: F, val: V), FacadeBool, generics: [F: ToRedisArgs + Send + Sync, V: ToRedisArgs + Send + Sync]);
+        $m!(hset_multiple, (values: &[(F, V)]), FacadeBool, generics: [F: ToRedisArgs + Send + Sync, V: ToRedisArgs + Send + Sync]);
+        $m!(hdel, (field: F), FacadeBool, generics: [F: ToRedisArgs + Send + Sync]);
+        $m!(hpersist, (field: F), FacadeBool, generics: [F: ToRedisArgs + Send + Sync]);
+        $m!(hkeys, (), Facade<T>, generics: [T: FromRedisValue]);
+        $m!(hvals, (), Facade<T>, generics: [T: FromRedisValue]);
+        $m!(hincr, (field: F, delta: D), Facade<RV>, generics: [F: ToRedisArgs + Send + Sync, D:  ToRedisArgs + Send + Sync ,RV:FromRedisValue]);
+        $m!(hlen, (), FacadeInt);
+        $m!(httl, (field:F), FacadeInt, generics: [F: ToRedisArgs + Send + Sync]);
+        $m!(hpttl, (field:F), FacadeInt, generics: [F: ToRedisArgs + Send + Sync]);
+        $m!(hexpire_time, (field:F), FacadeInt, generics: [F: ToRedisArgs + Send + Sync]);
+
+        //bit
+        $m!(getbit, (offset:usize), FacadeBool);
+        $m!(bitcount, (), FacadeInt);
+        $m!(bitcount_range, (start:usize, end:usize), FacadeInt);
+        $m!(setbit, (offset:usize, value:bool), FacadeBool);
+
+        // list operations
+        $m!(blmove, (dstkey: D as key, src_dir: redis::Direction, dst_dir: redis::Direction, timeout: f64), Facade<RV>, generics: [D:ToRedisArgs + Send + Sync, RV: FromRedisValue]);
+        $m!(blmpop, no_key, (timeout: f64, numkeys: usize, key: K as key, dir: redis::Direction, count: usize), Facade<RV>, generics: [K:ToRedisArgs + Send + Sync, RV: FromRedisValue]);
+        $m!(blpop, (timeout: f64), Facade<RV>, generics: [RV: FromRedisValue]);
+        $m!(brpop, (timeout: f64), Facade<RV>, generics: [RV: FromRedisValue]);
+        $m!(brpoplpush, (dstkey: D as key, timeout: f64), Facade<RV>, generics: [D:ToRedisArgs + Send + Sync, RV: FromRedisValue]);
+        $m!(lindex, (index: isize), Facade<RV>, generics: [RV: FromRedisValue]);
+        $m!(linsert_before, (pivot: P, value: V), FacadeBool, generics: [P: ToRedisArgs + Send + Sync, V: ToRedisArgs + Send + Sync]);
+        $m!(linsert_after, (pivot: P, value: V), FacadeBool, generics: [P: ToRedisArgs + Send + Sync, V: ToRedisArgs + Send + Sync]);
+        $m!(llen, (), FacadeInt);
+        $m!(lmove, (dstkey: D as key, src_dir: redis::Direction, dst_dir: redis::Direction), FacadeBool, generics: [D: ToRedisArgs + Send + Sync]);
+        $m!(lmpop, no_key, (numkeys: usize, key: K as key, dir: redis::Direction, count: usize), Facade<RV>, generics: [K: ToRedisArgs + Send + Sync, RV: FromRedisValue]);
+        $m!(lpop, (count: Option<core::num::NonZeroUsize>), Facade<RV>, generics: [RV: FromRedisValue]);
+        $m!(lpos, (value: V, options: redis::LposOptions), Facade<RV>, generics: [V:ToRedisArgs + Send + Sync, RV: FromRedisValue]);
+        $m!(lpush, (value: V), FacadeBool, generics: [V: ToRedisArgs + Send + Sync]);
+        $m!(lpush_exists, (value: V), FacadeBool, generics: [V: ToRedisArgs + Send + Sync]);
+        $m!(lrange, (start: isize, stop: isize),  Facade<RV>, generics: [RV: FromRedisValue]);
+        $m!(lrem, (count: isize, value: V),  Facade<RV>, generics: [V: ToRedisArgs + Send + Sync, RV: FromRedisValue]);
+        $m!(ltrim, (start: isize, stop: isize),  Facade<RV>, generics: [RV: FromRedisValue]);
+        $m!(lset, (index: isize, value: V),  Facade<RV>, generics: [V: ToRedisArgs + Send + Sync, RV: FromRedisValue]);
+        $m!(rpop, (count: Option<core::num::NonZeroUsize>), Facade<RV>, generics: [RV: FromRedisValue]);
+        $m!(rpoplpush, (dstkey: D as key), Facade<RV>, generics: [D:ToRedisArgs + Send + Sync, RV: FromRedisValue]);
+        $m!(rpush, (value: V), FacadeBool, generics: [V: ToRedisArgs + Send + Sync]);
+        $m!(rpush_exists, (value: V), FacadeBool, generics: [V: ToRedisArgs + Send + Sync]);
+
+        //set commands
+        $m!(sadd, (member: M), FacadeBool, generics: [M: ToRedisArgs + Send + Sync]);
+        $m!(scard, (), FacadeInt);
+        $m!(sdiff, (), Facade<RV>, generics: [RV: FromRedisValue]);
+        $m!(sdiffstore, no_key, (dest: D as key, keys: K as key), FacadeInt, generics: [D: ToRedisArgs + Send + Sync, K: ToRedisArgs + Send + Sync]);
+        $m!(sinter, (), Facade<RV>, generics: [RV: FromRedisValue]);
+        $m!(sinterstore, no_key, (dest: D as key, keys: K as key), FacadeInt, generics: [D: ToRedisArgs + Send + Sync, K: ToRedisArgs + Send + Sync]);
+        $m!(sismember, (member:M), FacadeBool,  generics: [M: ToRedisArgs + Send + Sync]);
+        $m!(smismember, (member:M), Facade<Vec<i8>>,  generics: [M: ToRedisArgs + Send + Sync]);
+        $m!(smembers, (), Facade<RV>, generics: [RV: FromRedisValue]);
+        $m!(smove, no_key, (srckey: S as key, dstkey: D as key, member: M), FacadeBool, generics: [S: ToRedisArgs + Send + Sync, D: ToRedisArgs + Send + Sync, M: ToRedisArgs + Send + Sync]);
+        $m!(spop, (), Facade<RV>, generics: [RV: FromRedisValue]);
+        $m!(srandmember, (), Facade<RV>, generics: [RV: FromRedisValue]);
+        $m!(srandmember_multiple, (count: usize), Facade<RV>, generics: [RV: FromRedisValue]);
+        $m!(srem, (member: M), FacadeBool, generics: [M: ToRedisArgs + Send + Sync]);
+        $m!(sunion, (), Facade<RV>,  generics: [RV: FromRedisValue]);
+        $m!(sunionstore, no_key, (dstkey: D as key, keys: K as key), Facade<RV>, generics: [ D: ToRedisArgs + Send + Sync, K: ToRedisArgs + Send + Sync, RV: FromRedisValue]);
+
+        // sorted set commands
+        $m!(zadd, (score: S, member: M), FacadeBool, generics: [S: ToRedisArgs + Send + Sync, M: ToRedisArgs + Send + Sync]);
+        $m!(zadd_multiple, (items: &[(S, M)]), FacadeBool, generics: [S: ToRedisArgs + Send + Sync, M: ToRedisArgs + Send + Sync]);
+        $m!(zcard, (), FacadeInt);
+        $m!(zcount, (min: M, max: M), FacadeInt, generics: [M: ToRedisArgs + Send + Sync]);
+        $m!(zincr, (member: M, delta:D), Facade<RV>, generics: [M: ToRedisArgs + Send + Sync, D: ToRedisArgs + Send + Sync, RV: FromRedisValue]);
+        $m!(zinterstore, no_key, (dest: D as key, keys: K as key), FacadeInt, generics: [D: ToRedisArgs + Send + Sync, K: ToRedisArgs + Send + Sync]);
+        $m!(zinterstore_min, no_key, (dest: D as key, keys: K as key), FacadeInt, generics: [D: ToRedisArgs + Send + Sync, K: ToRedisArgs + Send + Sync]);
+        $m!(zinterstore_max, no_key, (dest: D as key, keys: K as key), FacadeInt, generics: [D: ToRedisArgs + Send + Sync, K: ToRedisArgs + Send + Sync]);
+        $m!(zinterstore_weights, no_key, (dest: D as key, keys: &[(K, W)] as key_pairs), FacadeInt, generics: [D: ToRedisArgs + Send + Sync, K: ToRedisArgs + Send + Sync, W: ToRedisArgs + Send + Sync]);
+        $m!(zinterstore_max_weights, no_key, (dest: D as key, keys: &[(K, W)] as key_pairs), FacadeInt, generics: [D: ToRedisArgs + Send + Sync, K: ToRedisArgs + Send + Sync, W: ToRedisArgs + Send + Sync]);
+        $m!(zlexcount, (min: M, max: MM), FacadeInt, generics: [M: ToRedisArgs + Send + Sync, MM: ToRedisArgs + Send + Sync]);
+
+        $m!(bzpopmax, (timeout:f64), Facade<rs::Bzpoped>);
+        $m!(bzpopmin, (timeout:f64), Facade<rs::Bzpoped>);
+        $m!(zpopmax, (count:isize),  Facade<rs::Zpoped> );
+        $m!(zpopmin, (count:isize),  Facade<rs::Zpoped> );
     };
 }
 
-macro_rules! redis_b {
-    ($($method_name:ident),*) => {
-        $(
-            redis_c!($method_name, (), FacadeBool);
-        )*
-    };
-}
+pub mod blocking;
+pub mod codec;
+pub mod namespace;
+pub mod pipeline;
+pub mod pubsub;
+pub mod stream;
+
+/// commands blocking their connection up to the timeout, each runs on a connection of its own
+const BLOCKING_COMMANDS: [&str; 7] = ["blmove", "blmpop", "blpop", "brpop", "brpoplpush", "bzpopmax", "bzpopmin"];
+
+/// facades made by `Redis::named_in`, keyed by rings application and backend name, with the config version made at
+static NAMED: RwLock<BTreeMap<(String, String), (u64, Redis)>> = RwLock::new(BTreeMap::new());
 
 impl Redis {
-    pub fn shared() -> Self {
-        Redis { trace: true, client: crate::model::make_redis_client().unwrap() }
+    /// facade of the default redis backend of the default rings application, see `model::make_redis_client`
+    pub fn shared() -> Facade<Self> {
+        Self::shared_in(crate::rings::default_rings_name())
     }
 
-    pub fn new(c: redis::Client) -> Self {
-        Redis { trace: true, client: c }
+    /// facade of the default redis backend of the rings application
+    pub fn shared_in(app: &str) -> Facade<Self> {
+        Self::named_in(app, &crate::model::connections(app).default_redis_name()?)
     }
 
-    pub fn get_connection(&self) -> erx::ResultBoxedE<redis::Connection> {
-        self.client.get_connection().map_err(erx::simple_conv_boxed)
+    /// facade of the named redis backend of the default rings application, see `named_in`
+    /// # Arguments
+    /// * `name` - backend name
+    pub fn named(name: &str) -> Facade<Self> {
+        Self::named_in(crate::rings::default_rings_name(), name)
     }
 
-    redis_b!(exists, del, unlink, persist);
-    redis_i!(ttl, expire_time, strlen);
-
-    redis_c!(expire, (seconds: i64), FacadeBool);
-    redis_c!(expire_at, (expire_at: i64), FacadeBool);
-    redis_c!(rename, (new_key: N), FacadeBool, generics: [N: ToRedisArgs]);
-    redis_c!(rename_nx, (new_key: N), FacadeBool, generics: [N: ToRedisArgs]);
-    redis_c!(get, (), Facade<RV>, generics: [RV: FromRedisValue]);
-    redis_c!(mget, no_key, (key: &[K]), Facade<RV>, generics: [K:ToRedisArgs, RV: FromRedisValue]);
-    redis_c!(get_ex, (expire_at: u64 => redis::Expiry::EX(expire_at)), Facade<RV>, generics: [RV: FromRedisValue]);
-    redis_c!(getset,(val:V), Facade<RV>, generics: [V: ToRedisArgs, RV: FromRedisValue]);
-    redis_c!(getdel, redis: get_del, (), Facade<RV>,  generics: [RV: FromRedisValue]);
-    redis_c!(get_del, (), Facade<RV>, generics:[RV: FromRedisValue]);
-    redis_c!(getrange, (from:isize, to:isize), Facade<RV>, generics: [RV: FromRedisValue]);
-    redis_c!(append, (val: V), FacadeBool, generics: [V: ToRedisArgs]);
-    redis_c!(set, (val: V), FacadeBool, generics: [V: ToRedisArgs]);
-    redis_c!(mset, no_key, (items: &[(K, V)]), FacadeBool, generics: [K:ToRedisArgs, V: ToRedisArgs]);
-    // redis_c!(set_multiple, no_key, (items: &[(K, V)]), FacadeBool, generics: [K: ToRedisArgs, V: ToRedisArgs]);
-    redis_c!(set_options, (value:V, options:redis::SetOptions), FacadeBool, generics: [V: ToRedisArgs]);
-    redis_c!(set_ex, (val: V,  seconds: u64), FacadeBool, generics: [V: ToRedisArgs]);
-    redis_c!(set_nx, (val: V), FacadeBool, generics: [V: ToRedisArgs]);
-    redis_c!(setrange, (offset:isize, value:V), FacadeBool, generics: [V: ToRedisArgs]);
-    redis_c!(mset_nx, no_key, (items: &[(K, V)]),FacadeInt, generics: [K:ToRedisArgs, V: ToRedisArgs]);
-    redis_c!(incr, (delta: D), Facade<V>, generics: [D: ToRedisArgs , V: FromRedisValue]);
-    redis_c!(decr, (delta: D), Facade<V>, generics: [D: ToRedisArgs , V: FromRedisValue]);
-
-    //hash
-    redis_c!(hexists, (field: F), FacadeBool, generics: [F: ToRedisArgs]);
-    redis_c!(hget, (field: F), Facade<RV>, generics: [F: ToRedisArgs, RV: FromRedisValue]);
-
-    redis_c!(hget_ex, (fields: F, expire_at: u64 => {
-        redis::Expiry::EX(expire_at)
-    }), Facade<RV>, generics: [F: ToRedisArgs, RV: FromRedisValue]);
-
-    redis_c!(hgetall, ( ), Facade<RV>, generics: [RV: FromRedisValue]);
-    redis_c!(hget_del, ( field: F), Facade<RV>, generics: [F: ToRedisArgs, RV: FromRedisValue]);
-    redis_c!(hset, ( field: F, val: V), FacadeBool, generics: [F: ToRedisArgs, V: ToRedisArgs]);
-
-    redis_c!(hset_ex, (
-            expire_at: u64 => &{
-                redis::HashFieldExpirationOptions::default()
-                .set_expiration(
-                    redis::SetExpiry::EX(expire_at)
-                )
-            },
-            values: &[(F, V)]
-        ), FacadeBool, generics: [F: ToRedisArgs, V: ToRedisArgs]);
-
-    redis_c!(hset_nx, (field: F, val: V), FacadeBool, generics: [F: ToRedisArgs, V: ToRedisArgs]);
-    redis_c!(hset_multiple, (values: &[(F, V)]), FacadeBool, generics: [F: ToRedisArgs, V: ToRedisArgs]);
-    redis_c!(hdel, (field: F), FacadeBool, generics: [F: ToRedisArgs]);
-    redis_c!(hpersist, (field: F), FacadeBool, generics: [F: ToRedisArgs]);
-    redis_c!(hkeys, (), Facade<T>, generics: [T: FromRedisValue]);
-    redis_c!(hvals, (), Facade<T>, generics: [T: FromRedisValue]);
-    redis_c!(hincr, (field: F, delta: D), Facade<RV>, generics: [F: ToRedisArgs, D:  ToRedisArgs ,RV:FromRedisValue]);
-    redis_i!(hlen);
-    redis_c!(httl, (field:F), FacadeInt, generics: [F: ToRedisArgs]);
-    redis_c!(hpttl, (field:F), FacadeInt, generics: [F: ToRedisArgs]);
-    redis_c!(hexpire_time, (field:F), FacadeInt, generics: [F: ToRedisArgs]);
-
-    //bit
-    redis_c!(getbit, (offset:usize), FacadeBool);
-    redis_i!(bitcount);
-    redis_c!(bitcount_range, (start:usize, end:usize), FacadeInt);
-    redis_c!(setbit, (offset:usize, value:bool), FacadeBool);
-
-    // list operations
-    redis_c!(blmove, (dstkey: D, src_dir: redis::Direction, dst_dir: redis::Direction, timeout: f64), Facade<RV>, generics: [D:ToRedisArgs, RV: FromRedisValue]);
-    redis_c!(blmpop, no_key, (timeout: f64, numkeys: usize, key: K, dir: redis::Direction, count: usize), Facade<RV>, generics: [K:ToRedisArgs, RV: FromRedisValue]);
-    redis_c!(blpop, (timeout: f64), Facade<RV>, generics: [RV: FromRedisValue]);
-    redis_c!(brpop, (timeout: f64), Facade<RV>, generics: [RV: FromRedisValue]);
-    redis_c!(brpoplpush, (dstkey: D, timeout: f64), Facade<RV>, generics: [D:ToRedisArgs, RV: FromRedisValue]);
-    redis_c!(lindex, (index: isize), Facade<RV>, generics: [RV: FromRedisValue]);
-    redis_c!(linsert_before, (pivot: P, value: V), FacadeBool, generics: [P: ToRedisArgs, V: ToRedisArgs]);
-    redis_c!(linsert_after, (pivot: P, value: V), FacadeBool, generics: [P: ToRedisArgs, V: ToRedisArgs]);
-    redis_i!(llen);
-    redis_c!(lmove, (dstkey: D, src_dir: redis::Direction, dst_dir: redis::Direction), FacadeBool, generics: [D: ToRedisArgs]);
-    redis_c!(lmpop, no_key, (numkeys: usize, key: K, dir: redis::Direction, count: usize), Facade<RV>, generics: [K: ToRedisArgs, RV: FromRedisValue]);
-    redis_c!(lpop, (count: Option<core::num::NonZeroUsize>), Facade<RV>, generics: [RV: FromRedisValue]);
-    redis_c!(lpos, (value: V, options: redis::LposOptions), Facade<RV>, generics: [V:ToRedisArgs, RV: FromRedisValue]);
-    redis_c!(lpush, (value: V), FacadeBool, generics: [V: ToRedisArgs]);
-    redis_c!(lpush_exists, (value: V), FacadeBool, generics: [V: ToRedisArgs]);
-    redis_c!(lrange, (start: isize, stop: isize),  Facade<RV>, generics: [RV: FromRedisValue]);
-    redis_c!(lrem, (count: isize, value: V),  Facade<RV>, generics: [V: ToRedisArgs, RV: FromRedisValue]);
-    redis_c!(ltrim, (start: isize, stop: isize),  Facade<RV>, generics: [RV: FromRedisValue]);
-    redis_c!(lset, (index: isize, value: V),  Facade<RV>, generics: [V: ToRedisArgs, RV: FromRedisValue]);
-    redis_c!(rpop, (count: Option<core::num::NonZeroUsize>), Facade<RV>, generics: [RV: FromRedisValue]);
-    redis_c!(rpoplpush, (dstkey: D), Facade<RV>, generics: [D:ToRedisArgs, RV: FromRedisValue]);
-    redis_c!(rpush, (value: V), FacadeBool, generics: [V: ToRedisArgs]);
-    redis_c!(rpush_exists, (value: V), FacadeBool, generics: [V: ToRedisArgs]);
-
-    //set commands
-    redis_c!(sadd, (member: M), FacadeBool, generics: [M: ToRedisArgs]);
-    redis_i!(scard);
-    redis_c!(sdiff, (), Facade<RV>, generics: [RV: FromRedisValue]);
-    redis_c!(sdiffstore, no_key, (dest: D, keys:K), FacadeInt, generics: [D: ToRedisArgs, K: ToRedisArgs]);
-    redis_c!(sinter, (), Facade<RV>, generics: [RV: FromRedisValue]);
-    redis_c!(sinterstore, no_key, (dest: D, keys:K), FacadeInt, generics: [D: ToRedisArgs, K: ToRedisArgs]);
-    redis_c!(sismember, (member:M), FacadeBool,  generics: [M: ToRedisArgs]);
-    redis_c!(smismember, (member:M), Facade<Vec<i8>>,  generics: [M: ToRedisArgs]);
-    redis_c!(smembers, (), Facade<RV>, generics: [RV: FromRedisValue]);
-    redis_c!(smove, no_key, (srckey: S, dstkey: D, member: M), FacadeBool, generics: [S: ToRedisArgs, D: ToRedisArgs, M: ToRedisArgs]);
-    redis_c!(spop, (), Facade<RV>, generics: [RV: FromRedisValue]);
-    redis_c!(srandmember, (), Facade<RV>, generics: [RV: FromRedisValue]);
-    redis_c!(srandmember_multiple, (count: usize), Facade<RV>, generics: [RV: FromRedisValue]);
-    redis_c!(srem, (member: M), FacadeBool, generics: [M: ToRedisArgs]);
-    redis_c!(sunion, (), Facade<RV>,  generics: [RV: FromRedisValue]);
-    redis_c!(sunionstore, no_key, (dstkey: D, keys: K), Facade<RV>, generics: [ D: ToRedisArgs, K: ToRedisArgs, RV: FromRedisValue]);
-
-    // sorted set commands
-    redis_c!(zadd, (score: S, member: M), FacadeBool, generics: [S: ToRedisArgs, M: ToRedisArgs]);
-    redis_c!(zadd_multiple, (items: &[(S, M)]), FacadeBool, generics: [S: ToRedisArgs, M: ToRedisArgs]);
-    redis_i!(zcard);
-    redis_c!(zcount, (min: M, max: M), FacadeInt, generics: [M: ToRedisArgs]);
-    redis_c!(zincr, (member: M, delta:D), Facade<RV>, generics: [M: ToRedisArgs, D: ToRedisArgs, RV: FromRedisValue]);
-    redis_c!(zinterstore, no_key, (dest: D, keys:K), FacadeInt, generics: [D: ToRedisArgs, K: ToRedisArgs]);
-    redis_c!(zinterstore_min, no_key, (dest: D, keys:K), FacadeInt, generics: [D: ToRedisArgs, K: ToRedisArgs]);
-    redis_c!(zinterstore_max, no_key, (dest: D, keys:K), FacadeInt, generics: [D: ToRedisArgs, K: ToRedisArgs]);
-    redis_c!(zinterstore_weights, no_key, (dest: D, keys:&[(K, W)]), FacadeInt, generics: [D: ToRedisArgs, K: ToRedisArgs, W: ToRedisArgs]);
-    redis_c!(zinterstore_max_weights, no_key, (dest: D, keys:&[(K, W)]), FacadeInt, generics: [D: ToRedisArgs, K: ToRedisArgs, W: ToRedisArgs]);
-    redis_c!(zlexcount, (min: M, max: MM), FacadeInt, generics: [M: ToRedisArgs, MM: ToRedisArgs]);
-
-    redis_c!(bzpopmax, (timeout:f64), Facade<rs::Bzpoped>);
-    redis_c!(bzpopmin, (timeout:f64), Facade<rs::Bzpoped>);
-    redis_c!(zpopmax, (count:isize),  Facade<rs::Zpoped> );
-    redis_c!(zpopmin, (count:isize),  Facade<rs::Zpoped> );
-}
+    /// facade of the named redis backend of the rings application, keys in the namespace of the backend.
+    /// made once and shared, made again after config reloaded
+    /// # Arguments
+    /// * `app` - rings application name
+    /// * `name` - backend name
+    pub fn named_in(app: &str, name: &str) -> Facade<Self> {
+        let version = crate::conf::reload::version();
+        let key = (app.to_string(), name.to_string());
+        if let Some((_, found)) = NAMED.read().ok().and_then(|named| named.get(&key).filter(|(made_at, _)| *made_at == version).cloned()) {
+            return Ok(found);
+        }
 
-#[allow(dead_code)]
-#[cfg(test)]
-mod tests {
+        let connections = crate::model::connections(app);
+        let made = Self::new(connections.redis_client(name)?).with_namespace(&connections.redis_namespace(name));
+        let mut named = NAMED.write().map_err(erx::simple_conv_boxed)?;
+        named.insert(key, (version, made.clone()));
+        Ok(made)
+    }
 
-    use super::*;
-    use redis::{RedisError, RedisResult, RedisWrite, Value};
-    use serde::{Deserialize, Serialize};
-
-    #[derive(Debug, Serialize, Deserialize)]
-    struct Name {
-        key: String,
-        first: String,
-        middle: String,
-        last: String,
+    pub fn new(c: redis::Client) -> Self {
+        Redis { trace: true, client: c, connection: Arc::new(Mutex::new(None)), prefix: None }
     }
 
-    impl ToRedisArgs for Name {
-        fn write_redis_args<W>(&self, out: &mut W)
-        where
-            W: ?Sized + RedisWrite,
-        {
-            out.write_arg(serde_json::to_vec(self).unwrap().as_slice());
-        }
+    /// blocking facade of the same client, for sync callers only
+    pub fn blocking(&self) -> blocking::BlockingRedis {
+        blocking::BlockingRedis::new(self.client.clone()).with_namespace(self.namespace().unwrap_or_default())
     }
 
-    impl FromRedisValue for Name {
-        fn from_redis_value(v: &Value) -> RedisResult<Self> {
-            match v {
-                Value::BulkString(d) => Ok(serde_json::from_slice(d)?),
-                Value::Array(d) => {
-                    println!("----- {:?}", d);
-                    let e = RedisError::from((redis::ErrorKind::TypeError, "invalid type"));
-                    Err(e)
-                },
-                _ => {
-                    println!("==== {:?}", v);
-                    let e = RedisError::from((redis::ErrorKind::TypeError, "invalid type"));
-                    Err(e)
-                },
+    /// the shared multiplexed connection, made when there is none
+    pub async fn connection(&self) -> Facade<MultiplexedConnection> {
+        if let Some(connection) = self.connection.lock().map_err(erx::simple_conv_boxed)?.as_ref() {
+            return Ok(connection.clone());
+        }
+
+        let made = self.client.get_multiplexed_async_connection().await.map_err(|ex| {
+            if self.trace {
+                tracing::error!("Redis::connection {}", ex);
             }
+            erx::simple_conv_boxed(ex)
+        })?;
+
+        let mut connection = self.connection.lock().map_err(erx::simple_conv_boxed)?;
+        Ok(connection.get_or_insert(made).clone())
+    }
+
+    /// connection of a command: a connection of its own for `BLOCKING_COMMANDS`, they hold no one else up, the shared one otherwise
+    async fn connection_for(&self, command: &str) -> Facade<MultiplexedConnection> {
+        if !BLOCKING_COMMANDS.contains(&command) {
+            return self.connection().await;
         }
+
+        self.client.get_multiplexed_async_connection().await.map_err(|ex| {
+            if self.trace {
+                tracing::error!("Redis::connection_for {} {}", command, ex);
+            }
+            erx::simple_conv_boxed(ex)
+        })
     }
 
-    fn name_it() -> Name {
-        Name { key: "LJ".to_string(), first: "luo".to_string(), middle: "-".to_string(), last: "jing".to_string() }
+    /// command result into facade result, a broken connection is dropped to be made again on next command.
+    /// the failed command is not retried, it may have been applied
+    fn settle<T>(&self, result: redis::RedisResult<T>) -> Facade<T> {
+        result.map_err(|ex| {
+            if ex.is_io_error() || ex.is_connection_dropped() || ex.is_connection_refusal() || ex.is_unrecoverable_error() {
+                if let Ok(mut connection) = self.connection.lock() {
+                    connection.take();
+                }
+            }
+            if self.trace {
+                tracing::error!("Redis error: {}", ex);
+            }
+            erx::simple_conv_boxed(ex)
+        })
     }
 
-    #[test]
-    fn test_redis_value() {
-        let c = rds();
-        println!("{:?}", c.exists("test_ttl"));
-        println!("{:?}", c.set("test_ttl", "value"));
-        println!("{:?}", c.exists("test_ttl"));
-
-        // println!("{:?}", c.set_ex("test_ttl", 1024, 10));
-        println!("{:?}", c.ttl("test_ttl"));
-        // println!("{:?}", c.incr("test_ttl", 24));
-        // println!("{:?}", c.set::<_, Name>("LJ", name_it()));
-        // println!("{:?}", c.get::<_, Name>("LJ"));
-
-        // println!("hset {:?}", c.hset("LJHash", "d1age", 102410));
-        // println!("hset_multiple {:?}", c.hset_multiple("LJHash", &[("age", 1811), ("lastage", 24)]));
+    redis_commands!(redis_a);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_not_configured() {
+        assert!(Redis::named("not-configured-redis").is_err());
     }
 
-    fn rds() -> Redis {
-        Redis::new(redis::Client::open("redis://127.0.0.1").unwrap())
+    #[tokio::test]
+    async fn test_connection_refused() {
+        // nothing listens on port 1
+        let redis = Redis::new(redis::Client::open("redis://127.0.0.1:1").unwrap());
+        assert!(redis.exists("rings").await.is_err());
+        assert!(redis.connection.lock().unwrap().is_none());
+        assert!(redis.blpop::<&str, Option<(String, String)>>("rings", 0.1).await.is_err());
     }
 }
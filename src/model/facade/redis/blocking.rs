@@ -0,0 +1,168 @@
+use super::namespace::{self, Namespaced};
+use super::{rs, Facade, FacadeBool, FacadeInt};
+use crate::erx;
+use redis::{Commands, FromRedisValue, ToRedisArgs};
+
+/// Blocking Redis facade, for sync callers only: every command opens a connection,
+/// never use it in async code, use `Redis` there
+#[allow(dead_code)]
+pub struct BlockingRedis {
+    trace: bool,
+    client: redis::Client,
+    prefix: Option<String>,
+}
+
+macro_rules! redis_c {
+    // 基本形式：方法名、额外参数（不包括 key）、返回类型
+    ($method_name:ident, ($($arg_name:ident: $arg_type:ty $(as $role:ident)? $(=> $transform:expr)?),*), $return_type:ty) => {
+        pub fn $method_name<K: ToRedisArgs>(&self, key: K, $($arg_name: $arg_type),*) -> $return_type {
+            self.get_connection()?.$method_name(self.key(key), $(redis_c!(@process_arg self $arg_name $($role)? $($transform)?)),*).map_err(erx::simple_conv_boxed)
+        }
+    };
+
+    // 支持额外泛型参数
+    ($method_name:ident, ($($arg_name:ident: $arg_type:ty $(as $role:ident)? $(=> $transform:expr)?),*), $return_type:ty, generics: [$($generic:tt)*]) => {
+        pub fn $method_name<K: ToRedisArgs, $($generic)*>(&self, key: K, $($arg_name: $arg_type),*) -> $return_type {
+            self.get_connection()?.$method_name(self.key(key), $(redis_c!(@process_arg self $arg_name $($role)? $($transform)?)),*).map_err(erx::simple_conv_boxed)
+        }
+    };
+
+    // 支持 no_key：不添加 key: K
+    ($method_name:ident, no_key, ($($arg_name:ident: $arg_type:ty $(as $role:ident)? $(=> $transform:expr)?),*), $return_type:ty) => {
+        pub fn $method_name(&self, $($arg_name: $arg_type),*) -> $return_type {
+            self.get_connection()?.$method_name($(redis_c!(@process_arg self $arg_name $($role)? $($transform)?)),*).map_err(erx::simple_conv_boxed)
+        }
+    };
+
+    // 支持 no_key 和 generics
+    ($method_name:ident, no_key, ($($arg_name:ident: $arg_type:ty $(as $role:ident)? $(=> $transform:expr)?),*), $return_type:ty, generics: [$($generic:tt)*]) => {
+        pub fn $method_name<$($generic)*>(&self, $($arg_name: $arg_type),*) -> $return_type {
+            self.get_connection()?.$method_name($(redis_c!(@process_arg self $arg_name $($role)? $($transform)?)),*).map_err(erx::simple_conv_boxed)
+        }
+    };
+
+    // 支持显式指定 Redis 方法名
+    ($method_name:ident, redis: $redis_method:ident, ($($arg_name:ident: $arg_type:ty $(as $role:ident)? $(=> $transform:expr)?),*), $return_type:ty) => {
+        pub fn $method_name<K: ToRedisArgs>(&self, key: K, $($arg_name: $arg_type),*) -> $return_type {
+            self.get_connection()?.$redis_method(self.key(key), $(redis_c!(@process_arg self $arg_name $($role)? $($transform)?)),*).map_err(erx::simple_conv_boxed)
+        }
+    };
+
+    // 支持显式指定 Redis 方法名和 generics
+    ($method_name:ident, redis: $redis_method:ident, ($($arg_name:ident: $arg_type:ty $(as $role:ident)? $(=> $transform:expr)?),*), $return_type:ty, generics: [$($generic:tt)*]) => {
+        pub fn $method_name<K: ToRedisArgs, $($generic)*>(&self, key: K, $($arg_name: $arg_type),*) -> $return_type {
+            self.get_connection()?.$redis_method(self.key(key), $(redis_c!(@process_arg self $arg_name $($role)? $($transform)?)),*).map_err(erx::simple_conv_boxed)
+        }
+    };
+
+    // 辅助宏：处理单个参数，决定是使用转换表达式还是原始参数名，key 参数加上命名空间
+    (@process_arg $s:tt $arg_name:ident key) => { $s.key($arg_name) };
+    (@process_arg $s:tt $arg_name:ident key_pairs) => { &$s.key_pairs($arg_name) };
+    (@process_arg $s:tt $arg_name:ident $transform:expr) => { $transform };
+    (@process_arg $s:tt $arg_name:ident) => { $arg_name };
+}
+
+impl BlockingRedis {
+    /// blocking facade of the default redis backend
+    pub fn shared() -> Facade<Self> {
+        let connections = crate::model::connections(crate::rings::default_rings_name());
+        let name = connections.default_redis_name()?;
+        Ok(BlockingRedis::new(connections.redis_client(&name)?).with_namespace(&connections.redis_namespace(&name)))
+    }
+
+    pub fn new(c: redis::Client) -> Self {
+        BlockingRedis { trace: true, client: c, prefix: None }
+    }
+
+    /// the same facade with keys in `namespace`, empty for none, see `namespace`
+    pub fn with_namespace(mut self, namespace: &str) -> Self {
+        self.prefix = namespace::prefix(namespace);
+        self
+    }
+
+    /// the key, or keys, in the namespace of this facade
+    pub fn key<K>(&self, key: K) -> Namespaced<'_, K> {
+        Namespaced::new(self.prefix.as_deref(), key)
+    }
+
+    /// `(key, value)` pairs with keys in the namespace of this facade
+    pub fn key_pairs<'a, K, V>(&'a self, items: &'a [(K, V)]) -> Vec<(Namespaced<'a, &'a K>, &'a V)> {
+        items.iter().map(|(k, v)| (self.key(k), v)).collect()
+    }
+
+    pub fn get_connection(&self) -> erx::ResultBoxedE<redis::Connection> {
+        self.client.get_connection().map_err(erx::simple_conv_boxed)
+    }
+
+    redis_commands!(redis_c);
+}
+
+#[allow(dead_code)]
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use redis::{RedisError, RedisResult, RedisWrite, Value};
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Debug, Serialize, Deserialize)]
+    struct Name {
+        key: String,
+        first: String,
+        middle: String,
+        last: String,
+    }
+
+    impl ToRedisArgs for Name {
+        fn write_redis_args<W>(&self, out: &mut W)
+        where
+            W: ?Sized + RedisWrite,
+        {
+            out.write_arg(serde_json::to_vec(self).unwrap().as_slice());
+        }
+    }
+
+    impl FromRedisValue for Name {
+        fn from_redis_value(v: &Value) -> RedisResult<Self> {
+            match v {
+                Value::BulkString(d) => Ok(serde_json::from_slice(d)?),
+                Value::Array(d) => {
+                    println!("----- {:?}", d);
+                    let e = RedisError::from((redis::ErrorKind::TypeError, "invalid type"));
+                    Err(e)
+                },
+                _ => {
+                    println!("==== {:?}", v);
+                    let e = RedisError::from((redis::ErrorKind::TypeError, "invalid type"));
+                    Err(e)
+                },
+            }
+        }
+    }
+
+    fn name_it() -> Name {
+        Name { key: "LJ".to_string(), first: "luo".to_string(), middle: "-".to_string(), last: "jing".to_string() }
+    }
+
+    #[test]
+    fn test_redis_value() {
+        let c = rds();
+        println!("{:?}", c.exists("test_ttl"));
+        println!("{:?}", c.set("test_ttl", "value"));
+        println!("{:?}", c.exists("test_ttl"));
+
+        // println!("{:?}", c.set_ex("test_ttl", 1024, 10));
+        println!("{:?}", c.ttl("test_ttl"));
+        // println!("{:?}", c.incr("test_ttl", 24));
+        // println!("{:?}", c.set::<_, Name>("LJ", name_it()));
+        // println!("{:?}", c.get::<_, Name>("LJ"));
+
+        // println!("hset {:?}", c.hset("LJHash", "d1age", 102410));
+        // println!("hset_multiple {:?}", c.hset_multiple("LJHash", &[("age", 1811), ("lastage", 24)]));
+    }
+
+    fn rds() -> BlockingRedis {
+        BlockingRedis::new(redis::Client::open("redis://127.0.0.1").unwrap())
+    }
+}
+
@@ -0,0 +1,273 @@
+//! Typed values of the Redis facade
+//!
+//! values are encoded by a `Codec`: `Json` is built in, `MsgPack` comes with the `msgpack` feature,
+//! the compact binary `Bincode` with the `bincode` feature, others plug in by implementing `Codec`:
+//! ```ignore
+//! let user: Option<User> = redis.typed::<MsgPack>().get("user:1").await?;
+//! ```
+//! a missing key is `Ok(None)`, a value failing to decode is an error `is_decode_error` tells.
+
+use super::{Facade, FacadeBool, FacadeInt, Redis};
+use crate::erx::Erx;
+use redis::{AsyncCommands, ToRedisArgs};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::marker::PhantomData;
+
+/// extra key of the errors of values failing to decode
+pub const DECODE_ERROR: &str = "REDIS_DECODE";
+
+/// error is a value failing to decode, not a missing key or a redis failure
+pub fn is_decode_error(ex: &Erx) -> bool {
+    ex.extra_val(DECODE_ERROR).is_some()
+}
+
+/// Codec of typed values
+pub trait Codec: Send + Sync + 'static {
+    /// codec name, shown in errors
+    const NAME: &'static str;
+
+    fn encode<T: Serialize>(value: &T) -> Result<Vec<u8>, String>;
+
+    fn decode<T: DeserializeOwned>(bytes: &[u8]) -> Result<T, String>;
+}
+
+/// JSON codec
+pub struct Json;
+
+impl Codec for Json {
+    const NAME: &'static str = "json";
+
+    fn encode<T: Serialize>(value: &T) -> Result<Vec<u8>, String> {
+        serde_json::to_vec(value).map_err(|ex| ex.to_string())
+    }
+
+    fn decode<T: DeserializeOwned>(bytes: &[u8]) -> Result<T, String> {
+        serde_json::from_slice(bytes).map_err(|ex| ex.to_string())
+    }
+}
+
+/// MessagePack codec, structs encoded as maps so fields may be added or reordered
+#[cfg(feature = "msgpack")]
+pub struct MsgPack;
+
+#[cfg(feature = "msgpack")]
+impl Codec for MsgPack {
+    const NAME: &'static str = "msgpack";
+
+    fn encode<T: Serialize>(value: &T) -> Result<Vec<u8>, String> {
+        rmp_serde::to_vec_named(value).map_err(|ex| ex.to_string())
+    }
+
+    fn decode<T: DeserializeOwned>(bytes: &[u8]) -> Result<T, String> {
+        rmp_serde::from_slice(bytes).map_err(|ex| ex.to_string())
+    }
+}
+
+/// compact binary codec, fields encoded by position: values must be decoded as the type they were encoded from,
+/// self-describing types such as `serde_json::Value` are not supported
+#[cfg(feature = "bincode")]
+pub struct Bincode;
+
+#[cfg(feature = "bincode")]
+impl Codec for Bincode {
+    const NAME: &'static str = "bincode";
+
+    fn encode<T: Serialize>(value: &T) -> Result<Vec<u8>, String> {
+        bincode::serialize(value).map_err(|ex| ex.to_string())
+    }
+
+    fn decode<T: DeserializeOwned>(bytes: &[u8]) -> Result<T, String> {
+        bincode::deserialize(bytes).map_err(|ex| ex.to_string())
+    }
+}
+
+pub(super) fn encode<C: Codec, T: Serialize>(value: &T) -> Facade<Vec<u8>> {
+    C::encode(value).map_err(|ex| Erx::boxed(&format!("redis value {} encode failed: {}", C::NAME, ex)))
+}
+
+pub(super) fn decode<C: Codec, T: DeserializeOwned>(what: &str, bytes: &[u8]) -> Facade<T> {
+    C::decode(bytes).map_err(|ex| {
+        let mut erx = Erx::new(&format!("redis {} {} decode failed: {}", what, C::NAME, ex));
+        erx.add_extra(DECODE_ERROR, C::NAME);
+        erx.to_boxed()
+    })
+}
+
+fn decode_option<C: Codec, T: DeserializeOwned>(what: &str, bytes: Option<Vec<u8>>) -> Facade<Option<T>> {
+    bytes.map(|bytes| decode::<C, T>(what, &bytes)).transpose()
+}
+
+/// Typed view of a Redis facade, values encoded by `C`
+pub struct Typed<'a, C: Codec> {
+    pub(super) redis: &'a Redis,
+    codec: PhantomData<C>,
+}
+
+impl Redis {
+    /// typed view, values encoded by codec `C`
+    pub fn typed<C: Codec>(&self) -> Typed<'_, C> {
+        Typed { redis: self, codec: PhantomData }
+    }
+
+    /// `GET` a json value, `None` when the key is missing
+    pub async fn get_json<K: ToRedisArgs + Send + Sync, T: DeserializeOwned>(&self, key: K) -> Facade<Option<T>> {
+        self.typed::<Json>().get(key).await
+    }
+
+    /// `SET` a json value
+    pub async fn set_json<K: ToRedisArgs + Send + Sync, T: Serialize>(&self, key: K, value: &T) -> FacadeBool {
+        self.typed::<Json>().set(key, value).await
+    }
+
+    /// `SET` a json value with `EX` seconds
+    pub async fn set_json_ex<K: ToRedisArgs + Send + Sync, T: Serialize>(&self, key: K, value: &T, seconds: u64) -> FacadeBool {
+        self.typed::<Json>().set_ex(key, value, seconds).await
+    }
+
+    /// `MGET` json values, `None` for every missing key
+    pub async fn mget_json<K: ToRedisArgs + Send + Sync, T: DeserializeOwned>(&self, keys: &[K]) -> Facade<Vec<Option<T>>> {
+        self.typed::<Json>().mget(keys).await
+    }
+
+    /// `HGET` a json value, `None` when the key or the field is missing
+    pub async fn hget_json<K: ToRedisArgs + Send + Sync, F: ToRedisArgs + Send + Sync, T: DeserializeOwned>(&self, key: K, field: F) -> Facade<Option<T>> {
+        self.typed::<Json>().hget(key, field).await
+    }
+
+    /// `HSET` a json value
+    pub async fn hset_json<K: ToRedisArgs + Send + Sync, F: ToRedisArgs + Send + Sync, T: Serialize>(&self, key: K, field: F, value: &T) -> FacadeBool {
+        self.typed::<Json>().hset(key, field, value).await
+    }
+}
+
+impl<C: Codec> Typed<'_, C> {
+    async fn connection(&self) -> Facade<redis::aio::MultiplexedConnection> {
+        self.redis.connection().await
+    }
+
+    /// `GET`, `None` when the key is missing
+    pub async fn get<K: ToRedisArgs + Send + Sync, T: DeserializeOwned>(&self, key: K) -> Facade<Option<T>> {
+        let bytes: Option<Vec<u8>> = self.redis.settle(self.connection().await?.get(self.redis.key(key)).await)?;
+        decode_option::<C, T>("value", bytes)
+    }
+
+    /// `SET`
+    pub async fn set<K: ToRedisArgs + Send + Sync, T: Serialize>(&self, key: K, value: &T) -> FacadeBool {
+        let bytes = encode::<C, T>(value)?;
+        self.redis.settle(self.connection().await?.set(self.redis.key(key), bytes).await)
+    }
+
+    /// `SET` with `EX` seconds
+    pub async fn set_ex<K: ToRedisArgs + Send + Sync, T: Serialize>(&self, key: K, value: &T, seconds: u64) -> FacadeBool {
+        let bytes = encode::<C, T>(value)?;
+        self.redis.settle(self.connection().await?.set_ex(self.redis.key(key), bytes, seconds).await)
+    }
+
+    /// `MGET`, `None` for every missing key
+    pub async fn mget<K: ToRedisArgs + Send + Sync, T: DeserializeOwned>(&self, keys: &[K]) -> Facade<Vec<Option<T>>> {
+        if keys.is_empty() {
+            return Ok(vec![]);
+        }
+        let values: Vec<Option<Vec<u8>>> = self.redis.settle(redis::cmd("MGET").arg(self.redis.key(keys)).query_async(&mut self.connection().await?).await)?;
+        values.into_iter().map(|bytes| decode_option::<C, T>("value", bytes)).collect()
+    }
+
+    /// `HGET`, `None` when the key or the field is missing
+    pub async fn hget<K: ToRedisArgs + Send + Sync, F: ToRedisArgs + Send + Sync, T: DeserializeOwned>(&self, key: K, field: F) -> Facade<Option<T>> {
+        let bytes: Option<Vec<u8>> = self.redis.settle(self.connection().await?.hget(self.redis.key(key), field).await)?;
+        decode_option::<C, T>("hash field", bytes)
+    }
+
+    /// `HSET`
+    pub async fn hset<K: ToRedisArgs + Send + Sync, F: ToRedisArgs + Send + Sync, T: Serialize>(&self, key: K, field: F, value: &T) -> FacadeBool {
+        let bytes = encode::<C, T>(value)?;
+        self.redis.settle(self.connection().await?.hset(self.redis.key(key), field, bytes).await)
+    }
+
+    /// `HGETALL`, every field decoded
+    pub async fn hgetall<K: ToRedisArgs + Send + Sync, T: DeserializeOwned>(&self, key: K) -> Facade<HashMap<String, T>> {
+        let values: HashMap<String, Vec<u8>> = self.redis.settle(self.connection().await?.hgetall(self.redis.key(key)).await)?;
+        values.into_iter().map(|(field, bytes)| decode::<C, T>(&format!("hash field '{}'", field), &bytes).map(|v| (field, v))).collect()
+    }
+
+    /// `LPUSH`, returns the list length
+    pub async fn lpush<K: ToRedisArgs + Send + Sync, T: Serialize>(&self, key: K, value: &T) -> FacadeInt {
+        let bytes = encode::<C, T>(value)?;
+        self.redis.settle(self.connection().await?.lpush(self.redis.key(key), bytes).await)
+    }
+
+    /// `RPUSH`, returns the list length
+    pub async fn rpush<K: ToRedisArgs + Send + Sync, T: Serialize>(&self, key: K, value: &T) -> FacadeInt {
+        let bytes = encode::<C, T>(value)?;
+        self.redis.settle(self.connection().await?.rpush(self.redis.key(key), bytes).await)
+    }
+
+    /// `LRANGE`
+    pub async fn lrange<K: ToRedisArgs + Send + Sync, T: DeserializeOwned>(&self, key: K, start: isize, stop: isize) -> Facade<Vec<T>> {
+        let values: Vec<Vec<u8>> = self.redis.settle(self.connection().await?.lrange(self.redis.key(key), start, stop).await)?;
+        values.iter().map(|bytes| decode::<C, T>("list item", bytes)).collect()
+    }
+
+    /// `ZADD`
+    pub async fn zadd<K: ToRedisArgs + Send + Sync, T: Serialize>(&self, key: K, value: &T, score: f64) -> FacadeBool {
+        let bytes = encode::<C, T>(value)?;
+        self.redis.settle(self.connection().await?.zadd(self.redis.key(key), bytes, score).await)
+    }
+
+    /// `ZRANGE` by index, lowest score first
+    pub async fn zrange<K: ToRedisArgs + Send + Sync, T: DeserializeOwned>(&self, key: K, start: isize, stop: isize) -> Facade<Vec<T>> {
+        let values: Vec<Vec<u8>> = self.redis.settle(self.connection().await?.zrange(self.redis.key(key), start, stop).await)?;
+        values.iter().map(|bytes| decode::<C, T>("zset member", bytes)).collect()
+    }
+
+    /// `ZRANGE ... WITHSCORES` by index, lowest score first
+    pub async fn zrange_withscores<K: ToRedisArgs + Send + Sync, T: DeserializeOwned>(&self, key: K, start: isize, stop: isize) -> Facade<Vec<(T, f64)>> {
+        let values: Vec<(Vec<u8>, f64)> = self.redis.settle(self.connection().await?.zrange_withscores(self.redis.key(key), start, stop).await)?;
+        values.iter().map(|(bytes, score)| decode::<C, T>("zset member", bytes).map(|v| (v, *score))).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Deserialize;
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    struct Token {
+        user: i64,
+        scope: String,
+    }
+
+    #[test]
+    fn test_codec() {
+        let token = Token { user: 7, scope: "api".to_string() };
+        let bytes = encode::<Json, Token>(&token).unwrap();
+        assert_eq!(decode::<Json, Token>("value", &bytes).unwrap(), token);
+        assert_eq!(decode_option::<Json, Token>("value", None).unwrap(), None);
+
+        let broken = decode::<Json, Token>("value", b"{\"user\": \"seven\"}").unwrap_err();
+        assert!(is_decode_error(&broken));
+        assert!(!is_decode_error(&Erx::new("connection refused")));
+    }
+
+    #[cfg(feature = "msgpack")]
+    #[test]
+    fn test_msgpack() {
+        let token = Token { user: 7, scope: "api".to_string() };
+        let bytes = encode::<MsgPack, Token>(&token).unwrap();
+        assert_eq!(decode::<MsgPack, Token>("value", &bytes).unwrap(), token);
+        assert!(is_decode_error(&decode::<MsgPack, Token>("value", b"\xc1").unwrap_err()));
+    }
+
+    #[cfg(feature = "bincode")]
+    #[test]
+    fn test_bincode() {
+        let token = Token { user: 7, scope: "api".to_string() };
+        let bytes = encode::<Bincode, Token>(&token).unwrap();
+        assert!(bytes.len() < encode::<Json, Token>(&token).unwrap().len());
+        assert_eq!(decode::<Bincode, Token>("value", &bytes).unwrap(), token);
+        assert!(is_decode_error(&decode::<Bincode, Token>("value", &bytes[..4]).unwrap_err()));
+    }
+}
@@ -0,0 +1,201 @@
+//! Redis key namespaces
+//!
+//! apps sharing a redis keep their keys apart by a namespace, every key the framework touches is
+//! written as `{namespace}:{key}`. the namespace of a redis backend is its `namespace`, or the rebit
+//! `short` name (`name` when empty) if missing, an empty `namespace` turns it off:
+//! ```yaml
+//! model:
+//!   backends:
+//!     cache:
+//!       kind: redis
+//!       namespace: "billing"
+//!       connect: "redis://..."
+//! ```
+//! the `Redis` facade, its typed helpers and transactions namespace keys by themselves, keys queued on a
+//! `Pipeline` go through `Redis::key`. key names in replies, e.g. of `blpop`, keep the namespace.
+
+use super::{Facade, Redis};
+use crate::erx::Erx;
+use redis::{RedisWrite, ToRedisArgs};
+
+/// separator between namespace and key
+pub const SEPARATOR: &str = ":";
+
+/// keys scanned per `SCAN` round
+const SCAN_COUNT: usize = 500;
+
+/// key prefix of a namespace, `None` when the namespace is empty
+pub fn prefix(namespace: &str) -> Option<String> {
+    let namespace = namespace.trim();
+    (!namespace.is_empty()).then(|| format!("{}{}", namespace, SEPARATOR))
+}
+
+/// namespace of this application: the rebit `short` name, or `name` when empty
+pub async fn app_namespace() -> String {
+    let rebit = crate::conf::rebit().read().await;
+    if rebit.short.trim().is_empty() { rebit.name.clone() } else { rebit.short.clone() }
+}
+
+/// key within a namespace, for the callers building keys as strings
+/// # Arguments
+/// * `namespace` - the namespace, empty for none
+/// * `key` - the raw key
+pub fn namespaced(namespace: &str, key: &str) -> String {
+    match prefix(namespace) {
+        Some(prefix) => format!("{}{}", prefix, key),
+        None => key.to_string(),
+    }
+}
+
+/// glob characters escaped, for a literal prefix of `SCAN MATCH` or `PSUBSCRIBE` patterns
+pub(super) fn escape_glob(literal: &str) -> String {
+    let mut escaped = String::with_capacity(literal.len());
+    for c in literal.chars() {
+        if matches!(c, '*' | '?' | '[' | ']' | '\\') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+/// `SCAN MATCH` pattern of every key under a prefix
+fn match_all(prefix: &str) -> String {
+    format!("{}*", escape_glob(prefix))
+}
+
+/// Namespaced key, every redis argument of `key` prefixed, so a slice of keys is namespaced key by key
+/// # Fields
+/// * `prefix` - key prefix, `None` leaves the key as is
+/// * `key` - the raw key, or keys
+pub struct Namespaced<'a, K> {
+    prefix: Option<&'a str>,
+    key: K,
+}
+
+impl<'a, K> Namespaced<'a, K> {
+    pub fn new(prefix: Option<&'a str>, key: K) -> Self {
+        Namespaced { prefix, key }
+    }
+}
+
+impl<K: ToRedisArgs> ToRedisArgs for Namespaced<'_, K> {
+    fn write_redis_args<W>(&self, out: &mut W)
+    where
+        W: ?Sized + RedisWrite,
+    {
+        match self.prefix {
+            None => self.key.write_redis_args(out),
+            Some(prefix) => {
+                for arg in self.key.to_redis_args() {
+                    out.write_arg(&[prefix.as_bytes(), &arg].concat());
+                }
+            },
+        }
+    }
+
+    fn num_of_args(&self) -> usize {
+        self.key.num_of_args()
+    }
+}
+
+impl Redis {
+    /// namespace of this facade, `None` when keys are not namespaced
+    pub fn namespace(&self) -> Option<&str> {
+        self.prefix.as_deref().and_then(|prefix| prefix.strip_suffix(SEPARATOR))
+    }
+
+    /// the same facade with keys in `namespace`, empty for none
+    pub fn with_namespace(mut self, namespace: &str) -> Self {
+        self.prefix = prefix(namespace);
+        self
+    }
+
+    /// the key, or keys, in the namespace of this facade
+    pub fn key<K>(&self, key: K) -> Namespaced<'_, K> {
+        Namespaced::new(self.prefix.as_deref(), key)
+    }
+
+    /// `(key, value)` pairs with keys in the namespace of this facade
+    pub fn key_pairs<'a, K, V>(&'a self, items: &'a [(K, V)]) -> Vec<(Namespaced<'a, &'a K>, &'a V)> {
+        items.iter().map(|(k, v)| (self.key(k), v)).collect()
+    }
+
+    /// delete every key of the namespace, by `SCAN` and `UNLINK`
+    /// # Returns
+    /// * `u64` - keys deleted
+    /// * `Err` - the facade has no namespace, it would delete the whole database
+    pub async fn purge_namespace(&self) -> Facade<u64> {
+        self.each_in_namespace(|pipe, keys| {
+            pipe.unlink(keys);
+        })
+        .await
+    }
+
+    /// set a time to live on every key of the namespace, by `SCAN` and `EXPIRE`
+    /// # Returns
+    /// * `u64` - keys the time to live is set on
+    /// * `Err` - the facade has no namespace
+    pub async fn expire_namespace(&self, seconds: i64) -> Facade<u64> {
+        self.each_in_namespace(|pipe, keys| {
+            keys.iter().for_each(|key| {
+                pipe.expire(key, seconds);
+            });
+        })
+        .await
+    }
+
+    /// scan the keys of the namespace, `apply` queues the commands of every batch, their replies are counts summed up
+    async fn each_in_namespace<F: Fn(&mut redis::Pipeline, &[Vec<u8>])>(&self, apply: F) -> Facade<u64> {
+        let prefix = self.prefix.as_deref().ok_or(Erx::boxed("redis facade has no namespace, refused to touch every key"))?;
+        let pattern = match_all(prefix);
+        let mut conn = self.connection().await?;
+
+        let (mut cursor, mut touched) = (0u64, 0u64);
+        loop {
+            let (next, keys): (u64, Vec<Vec<u8>>) =
+                self.settle(redis::cmd("SCAN").arg(cursor).arg("MATCH").arg(&pattern).arg("COUNT").arg(SCAN_COUNT).query_async(&mut conn).await)?;
+            if !keys.is_empty() {
+                let mut pipe = redis::pipe();
+                apply(&mut pipe, &keys);
+                let counts: Vec<u64> = self.settle(pipe.query_async(&mut conn).await)?;
+                touched += counts.iter().sum::<u64>();
+            }
+            if next == 0 {
+                return Ok(touched);
+            }
+            cursor = next;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_namespaced() {
+        assert_eq!(prefix("billing").as_deref(), Some("billing:"));
+        assert_eq!(prefix("  "), None);
+        assert_eq!(namespaced("billing", "XR:7"), "billing:XR:7");
+        assert_eq!(namespaced("", "XR:7"), "XR:7");
+
+        let args = Namespaced::new(Some("billing:"), &["a", "b"][..]).to_redis_args();
+        assert_eq!(args, vec![b"billing:a".to_vec(), b"billing:b".to_vec()]);
+        assert_eq!(Namespaced::new(None, "a").to_redis_args(), vec![b"a".to_vec()]);
+
+        assert_eq!(match_all("a*b[1]:"), "a\\*b\\[1\\]:*");
+    }
+
+    #[tokio::test]
+    async fn test_facade_namespace() {
+        let redis = Redis::new(redis::Client::open("redis://127.0.0.1:1").unwrap());
+        assert_eq!(redis.namespace(), None);
+        assert!(redis.purge_namespace().await.is_err());
+
+        let redis = redis.with_namespace("billing");
+        assert_eq!(redis.namespace(), Some("billing"));
+        assert_eq!(redis.key("a").to_redis_args(), vec![b"billing:a".to_vec()]);
+        assert_eq!(redis.blocking().key("a").to_redis_args(), vec![b"billing:a".to_vec()]);
+    }
+}
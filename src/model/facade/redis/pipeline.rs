@@ -0,0 +1,258 @@
+//! Redis pipelines and optimistic transactions
+//!
+//! a `Pipeline` queues the facade commands, the same names and arguments, keys in the namespace of the
+//! facade it was made by, and sends them in one round trip, results come back typed:
+//! ```ignore
+//! let mut pipe = redis.pipeline();
+//! pipe.set("XR:a", 1).ignore().incr("XR:b", 2).get("XR:a");
+//! let (b, a): (i64, i64) = pipe.query(&redis).await?;
+//! ```
+//! `Redis::transaction` runs WATCH/MULTI/EXEC, the closure reads through the watched connection
+//! and returns the pipeline to commit, it is run again while a watched key changed:
+//! ```ignore
+//! let (balance,): (i64,) = redis.transaction(&["XR:balance"], 5, |watched| async move {
+//!     let balance: i64 = watched.get("XR:balance").await?;
+//!     let mut pipe = watched.pipeline();
+//!     pipe.set("XR:balance", balance - 10).ignore().get("XR:balance");
+//!     Ok(pipe)
+//! }).await?;
+//! ```
+
+use super::namespace::Namespaced;
+use super::{Facade, Redis};
+use crate::erx::{self, Erx};
+use redis::aio::MultiplexedConnection;
+use redis::{FromRedisValue, ToRedisArgs};
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+
+/// keys of a pipeline in its namespace, borrowed apart from the pipeline queuing them
+struct Keys<'a>(Option<&'a str>);
+
+impl<'a> Keys<'a> {
+    fn key<K>(&self, key: K) -> Namespaced<'a, K> {
+        Namespaced::new(self.0, key)
+    }
+
+    fn key_pairs<'b, K, V>(&self, items: &'b [(K, V)]) -> Vec<(Namespaced<'a, &'b K>, &'b V)> {
+        items.iter().map(|(k, v)| (self.key(k), v)).collect()
+    }
+}
+
+/// the command methods of `Pipeline`, queuing instead of sending, the result types dropped:
+/// generics bound by `FromRedisValue` only type results, they are typed at `Pipeline::query`
+macro_rules! redis_p {
+    ($method_name:ident, ($($arg:tt)*), $return_type:ty) => {
+        redis_p!(@emit key $method_name $method_name [] ($($arg)*));
+    };
+    ($method_name:ident, ($($arg:tt)*), $return_type:ty, generics: [$($generic:tt)*]) => {
+        redis_p!(@generics key $method_name $method_name [] [$($generic)*] ($($arg)*));
+    };
+    ($method_name:ident, no_key, ($($arg:tt)*), $return_type:ty) => {
+        redis_p!(@emit no_key $method_name $method_name [] ($($arg)*));
+    };
+    ($method_name:ident, no_key, ($($arg:tt)*), $return_type:ty, generics: [$($generic:tt)*]) => {
+        redis_p!(@generics no_key $method_name $method_name [] [$($generic)*] ($($arg)*));
+    };
+    ($method_name:ident, redis: $redis_method:ident, ($($arg:tt)*), $return_type:ty) => {
+        redis_p!(@emit key $method_name $redis_method [] ($($arg)*));
+    };
+    ($method_name:ident, redis: $redis_method:ident, ($($arg:tt)*), $return_type:ty, generics: [$($generic:tt)*]) => {
+        redis_p!(@generics key $method_name $redis_method [] [$($generic)*] ($($arg)*));
+    };
+
+    // 过滤泛型参数：去掉只约束返回值的 `FromRedisValue`，保留其余
+    (@generics $kind:ident $method_name:ident $redis_method:ident [$($kept:tt)*] [] $args:tt) => {
+        redis_p!(@emit $kind $method_name $redis_method [$($kept)*] $args);
+    };
+    (@generics $kind:ident $method_name:ident $redis_method:ident [$($kept:tt)*] [$g:ident : FromRedisValue $(, $($rest:tt)*)?] $args:tt) => {
+        redis_p!(@generics $kind $method_name $redis_method [$($kept)*] [$($($rest)*)?] $args);
+    };
+    (@generics $kind:ident $method_name:ident $redis_method:ident [$($kept:tt)*] [$g:ident : $b:ident $(+ $bs:ident)* $(, $($rest:tt)*)?] $args:tt) => {
+        redis_p!(@generics $kind $method_name $redis_method [$($kept)* $g: $b $(+ $bs)*,] [$($($rest)*)?] $args);
+    };
+
+    (@emit key $method_name:ident $redis_method:ident [$($kept:tt)*] ($($arg_name:ident: $arg_type:ty $(as $role:ident)? $(=> $transform:expr)?),*)) => {
+        pub fn $method_name<K: ToRedisArgs + Send + Sync, $($kept)*>(&mut self, key: K, $($arg_name: $arg_type),*) -> &mut Self {
+            let keys = Keys(self.prefix.as_deref());
+            self.pipe.$redis_method(keys.key(key), $(redis_a!(@process_arg keys $arg_name $($role)? $($transform)?)),*);
+            self
+        }
+    };
+    (@emit no_key $method_name:ident $redis_method:ident [$($kept:tt)*] ($($arg_name:ident: $arg_type:ty $(as $role:ident)? $(=> $transform:expr)?),*)) => {
+        pub fn $method_name<$($kept)*>(&mut self, $($arg_name: $arg_type),*) -> &mut Self {
+            let keys = Keys(self.prefix.as_deref());
+            self.pipe.$redis_method($(redis_a!(@process_arg keys $arg_name $($role)? $($transform)?)),*);
+            self
+        }
+    };
+}
+
+/// Redis Pipeline, the command methods of the `Redis` facade queuing commands, results typed at `query`.
+/// keys are in the namespace of the facade it was made by, see `Redis::pipeline`
+/// # Fields
+/// * `pipe` - the commands queued
+/// * `prefix` - key prefix of the namespace
+#[derive(Clone, Default)]
+pub struct Pipeline {
+    pipe: redis::Pipeline,
+    prefix: Option<String>,
+}
+
+impl Pipeline {
+    /// pipeline of keys not namespaced, `Redis::pipeline` namespaces them
+    pub fn new() -> Self {
+        Pipeline { pipe: redis::pipe(), prefix: None }
+    }
+
+    /// the key, or keys, in the namespace of this pipeline, for commands added by `add_command`
+    pub fn key<K>(&self, key: K) -> Namespaced<'_, K> {
+        Namespaced::new(self.prefix.as_deref(), key)
+    }
+
+    /// drop the result of the last command queued
+    pub fn ignore(&mut self) -> &mut Self {
+        self.pipe.ignore();
+        self
+    }
+
+    /// queue a command as it is, its keys not namespaced, see `key`
+    pub fn add_command(&mut self, cmd: redis::Cmd) -> &mut Self {
+        self.pipe.add_command(cmd);
+        self
+    }
+
+    /// commands queued
+    pub fn len(&self) -> usize {
+        self.pipe.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.pipe.is_empty()
+    }
+
+    /// send the commands in one round trip over the shared connection
+    /// # Returns
+    /// * `T` - results of the commands not ignored, usually a tuple in queue order
+    pub async fn query<T: FromRedisValue>(&self, redis: &Redis) -> Facade<T> {
+        let mut conn = redis.connection().await?;
+        redis.settle(self.pipe.query_async(&mut conn).await)
+    }
+
+    /// send the commands, results dropped
+    pub async fn exec(&self, redis: &Redis) -> Facade<()> {
+        self.query::<()>(redis).await
+    }
+
+    redis_commands!(redis_p);
+}
+
+impl Redis {
+    /// new empty pipeline, keys in the namespace of this facade
+    pub fn pipeline(&self) -> Pipeline {
+        Pipeline { pipe: redis::pipe(), prefix: self.prefix.clone() }
+    }
+
+    /// optimistic transaction: WATCH `keys`, run `body`, commit its pipeline in MULTI/EXEC.
+    /// when a watched key changed, or EXEC was aborted, `body` is run again, `attempts` times at most.
+    ///
+    /// WATCH belongs to a connection, so the transaction runs on a connection of its own,
+    /// `body` gets a facade over it to read the watched keys.
+    ///
+    /// # Arguments
+    /// * `keys` - keys to watch
+    /// * `attempts` - max runs of `body`, at least 1
+    /// * `body` - reads through the given facade, returns the pipeline to commit, an `Err` aborts
+    ///
+    /// # Returns
+    /// * `T` - results of the committed pipeline
+    /// * `Err` - `body` failed, a command failed, or attempts exhausted
+    pub async fn transaction<K, T, F, Fut>(&self, keys: &[K], attempts: usize, mut body: F) -> Facade<T>
+    where
+        K: ToRedisArgs + Send + Sync,
+        T: FromRedisValue,
+        F: FnMut(Redis) -> Fut,
+        Fut: Future<Output = Facade<Pipeline>>,
+    {
+        let mut conn = self.client.get_multiplexed_async_connection().await.map_err(|ex| self.traced(ex))?;
+        let attempts = attempts.max(1);
+
+        for attempt in 1..=attempts {
+            if !keys.is_empty() {
+                redis::cmd("WATCH").arg(self.key(keys)).exec_async(&mut conn).await.map_err(|ex| self.traced(ex))?;
+            }
+
+            let watched = Redis { trace: self.trace, client: self.client.clone(), connection: Arc::new(Mutex::new(Some(conn.clone()))), prefix: self.prefix.clone() };
+            let mut pipeline = match body(watched).await {
+                Ok(pipeline) => pipeline,
+                Err(ex) => {
+                    unwatch(&mut conn).await;
+                    return Err(ex);
+                },
+            };
+
+            match pipeline.pipe.atomic().query_async::<Option<T>>(&mut conn).await {
+                Ok(Some(result)) => return Ok(result),
+                Ok(None) => tracing::debug!("redis transaction, watched keys changed, attempt {}/{}", attempt, attempts),
+                Err(ex) if ex.kind() == redis::ErrorKind::ExecAbortError => {
+                    unwatch(&mut conn).await;
+                    tracing::warn!("redis transaction EXECABORT, attempt {}/{}: {}", attempt, attempts, ex);
+                },
+                Err(ex) => {
+                    unwatch(&mut conn).await;
+                    return Err(self.traced(ex));
+                },
+            }
+        }
+
+        Err(Erx::boxed(&format!("redis transaction aborted after {} attempts", attempts)))
+    }
+
+    fn traced(&self, ex: redis::RedisError) -> Box<Erx> {
+        if self.trace {
+            tracing::error!("Redis error: {}", ex);
+        }
+        erx::simple_conv_boxed(ex)
+    }
+}
+
+/// drop the watches left by an aborted transaction, failures ignored, the connection is dropped after all
+async fn unwatch(conn: &mut MultiplexedConnection) {
+    let _ = redis::cmd("UNWATCH").exec_async(conn).await;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pipeline() {
+        let mut pipe = Pipeline::new();
+        assert!(pipe.is_empty());
+
+        pipe.set("XR:a", 1).ignore().incr("XR:b", 2).get("XR:a").add_command(redis::cmd("PING"));
+        assert_eq!(pipe.len(), 4);
+
+        let redis = Redis::new(redis::Client::open("redis://127.0.0.1:1/").unwrap()).with_namespace("billing");
+        let mut pipe = redis.pipeline();
+        pipe.get("XR:a").mget(&["XR:b", "XR:c"]).rename("XR:a", "XR:d");
+        let packed = String::from_utf8_lossy(&pipe.pipe.get_packed_pipeline()).to_string();
+        for key in ["billing:XR:a", "billing:XR:b", "billing:XR:c", "billing:XR:d"] {
+            assert!(packed.contains(key), "{} not namespaced", key);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_transaction_connection_refused() {
+        let redis = Redis::new(redis::Client::open("redis://127.0.0.1:1/").unwrap());
+        let mut runs = 0;
+        let result: Facade<()> = redis
+            .transaction(&["XR:a"], 3, |_| {
+                runs += 1;
+                async { Ok(Pipeline::new()) }
+            })
+            .await;
+        assert!(result.is_err());
+        assert_eq!(runs, 0);
+    }
+}
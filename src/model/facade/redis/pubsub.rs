@@ -0,0 +1,151 @@
+//! Redis Pub/Sub
+//!
+//! channels are in the namespace of the facade the same way keys are, messages are typed by a `Codec`:
+//! ```ignore
+//! redis.publish_json("orders", &order).await?;
+//!
+//! let mut subscription = redis.psubscribe(&["orders.*"]).await?;
+//! while let Some(message) = subscription.next().await {
+//!     let order: Order = message.decode()?;
+//! }
+//! ```
+//! a subscription holds a connection of its own, `next` gives `None` once it is lost, subscribe again then.
+
+use super::codec::{self, Codec, Json, Typed};
+use super::namespace::escape_glob;
+use super::{Facade, FacadeInt, Redis};
+use crate::erx;
+use futures_util::StreamExt;
+use redis::{AsyncCommands, ToRedisArgs};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+/// Pub/Sub Message
+/// # Fields
+/// * `channel` - channel published to, namespace removed
+/// * `pattern` - pattern subscribed to, when the message came by `psubscribe`
+/// * `payload` - message payload
+#[derive(Debug, Clone)]
+pub struct Message {
+    pub channel: String,
+    pub pattern: Option<String>,
+    pub payload: Vec<u8>,
+}
+
+impl Message {
+    /// payload decoded as json
+    pub fn decode<T: DeserializeOwned>(&self) -> Facade<T> {
+        self.decode_with::<Json, T>()
+    }
+
+    /// payload decoded by codec `C`, failures tell by `codec::is_decode_error`
+    pub fn decode_with<C: Codec, T: DeserializeOwned>(&self) -> Facade<T> {
+        codec::decode::<C, T>(&format!("message of channel '{}'", self.channel), &self.payload)
+    }
+}
+
+/// Pub/Sub Subscription
+/// # Fields
+/// * `stream` - messages of the subscription connection
+/// * `prefix` - namespace prefix removed from the channels received
+/// * `pattern_prefix` - namespace prefix, glob escaped, removed from the patterns received
+pub struct Subscription {
+    stream: redis::aio::PubSubStream,
+    prefix: Option<String>,
+    pattern_prefix: Option<String>,
+}
+
+/// the name without the namespace prefix, as it is when not in the namespace
+fn strip_namespace(name: String, prefix: Option<&str>) -> String {
+    match prefix {
+        Some(prefix) => name.strip_prefix(prefix).map(String::from).unwrap_or(name),
+        None => name,
+    }
+}
+
+impl Subscription {
+    /// next message, `None` when the connection is lost
+    pub async fn next(&mut self) -> Option<Message> {
+        let msg = self.stream.next().await?;
+
+        Some(Message {
+            channel: strip_namespace(msg.get_channel_name().to_string(), self.prefix.as_deref()),
+            pattern: msg.get_pattern::<Option<String>>().ok().flatten().map(|pattern| strip_namespace(pattern, self.pattern_prefix.as_deref())),
+            payload: msg.get_payload_bytes().to_vec(),
+        })
+    }
+}
+
+impl Redis {
+    /// `PUBLISH` a message to the channel
+    /// # Returns
+    /// * `i64` - subscribers received it
+    pub async fn publish<M: ToRedisArgs + Send + Sync>(&self, channel: &str, message: M) -> FacadeInt {
+        let mut conn = self.connection().await?;
+        self.settle(conn.publish(self.key(channel), message).await)
+    }
+
+    /// `PUBLISH` a json message to the channel
+    pub async fn publish_json<T: Serialize>(&self, channel: &str, message: &T) -> FacadeInt {
+        self.typed::<Json>().publish(channel, message).await
+    }
+
+    /// `SUBSCRIBE` channels, on a connection of its own
+    pub async fn subscribe(&self, channels: &[&str]) -> Facade<Subscription> {
+        self.listen(channels, &[]).await
+    }
+
+    /// `PSUBSCRIBE` channel patterns, glob-style, on a connection of its own
+    pub async fn psubscribe(&self, patterns: &[&str]) -> Facade<Subscription> {
+        self.listen(&[], patterns).await
+    }
+
+    /// subscribe both channels and patterns on one connection
+    pub async fn listen(&self, channels: &[&str], patterns: &[&str]) -> Facade<Subscription> {
+        let mut pubsub = self.client.get_async_pubsub().await.map_err(erx::simple_conv_boxed)?;
+        if !channels.is_empty() {
+            pubsub.subscribe(self.key(channels)).await.map_err(erx::simple_conv_boxed)?;
+        }
+        let pattern_prefix = self.prefix.as_deref().map(escape_glob);
+        if !patterns.is_empty() {
+            let prefix = pattern_prefix.as_deref().unwrap_or_default();
+            let patterns: Vec<String> = patterns.iter().map(|pattern| format!("{}{}", prefix, pattern)).collect();
+            pubsub.psubscribe(patterns).await.map_err(erx::simple_conv_boxed)?;
+        }
+
+        Ok(Subscription { stream: pubsub.into_on_message(), prefix: self.prefix.clone(), pattern_prefix })
+    }
+}
+
+impl<C: Codec> Typed<'_, C> {
+    /// `PUBLISH` a message encoded by `C`
+    pub async fn publish<T: Serialize>(&self, channel: &str, message: &T) -> FacadeInt {
+        let bytes = codec::encode::<C, T>(message)?;
+        self.redis.publish(channel, bytes).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_message() {
+        let message = Message { channel: "orders".to_string(), pattern: None, payload: b"{\"id\": 7}".to_vec() };
+        let decoded: serde_json::Value = message.decode().unwrap();
+        assert_eq!(decoded["id"], 7);
+
+        let broken = Message { payload: b"not json".to_vec(), ..message };
+        assert!(codec::is_decode_error(&broken.decode::<serde_json::Value>().unwrap_err()));
+    }
+
+    #[test]
+    fn test_strip_namespace() {
+        let prefix = "shop[1]:";
+        let escaped = escape_glob(prefix);
+        assert_eq!(strip_namespace("shop[1]:orders.7".to_string(), Some(prefix)), "orders.7");
+        assert_eq!(strip_namespace(format!("{}orders.*", escaped), Some(&escaped)), "orders.*");
+        assert_eq!(strip_namespace("other:orders.7".to_string(), Some(prefix)), "other:orders.7");
+        assert_eq!(strip_namespace("orders.*".to_string(), None), "orders.*");
+    }
+}
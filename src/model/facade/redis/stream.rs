@@ -0,0 +1,197 @@
+//! Redis Streams
+//!
+//! producers `XADD` entries, consumers of a group read them and `XACK` what they handled,
+//! entries a dead consumer left pending are claimed by another after idling long enough:
+//! ```ignore
+//! redis.xadd_json("orders", &order, Some(100_000)).await?;
+//!
+//! let consumer = redis.consumer("orders", "billing", "billing-1").await?;
+//! loop {
+//!     let mut entries = consumer.claim_stuck(Duration::from_secs(60), 10).await?;
+//!     entries.extend(consumer.read(10, Some(Duration::from_secs(5))).await?);
+//!     for entry in entries {
+//!         let order: Order = stream::decode(&entry)?;
+//!         consumer.ack(&[&entry.id]).await?;
+//!     }
+//! }
+//! ```
+//! typed entries keep the encoded value in the field `PAYLOAD_FIELD`.
+
+use super::codec::{self, Codec, Json, Typed};
+use super::{Facade, FacadeInt, Redis};
+use crate::erx::Erx;
+use redis::streams::{StreamClaimReply, StreamId, StreamMaxlen, StreamPendingCountReply, StreamPendingId, StreamReadOptions, StreamReadReply};
+use redis::{AsyncCommands, ToRedisArgs};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// field of typed entries holding the encoded value
+pub const PAYLOAD_FIELD: &str = "payload";
+
+/// value of a typed entry, decoded as json
+pub fn decode<T: DeserializeOwned>(entry: &StreamId) -> Facade<T> {
+    decode_with::<Json, T>(entry)
+}
+
+/// value of a typed entry, decoded by codec `C`, failures tell by `codec::is_decode_error`
+pub fn decode_with<C: Codec, T: DeserializeOwned>(entry: &StreamId) -> Facade<T> {
+    let bytes: Vec<u8> = entry.get(PAYLOAD_FIELD).ok_or(Erx::boxed(&format!("redis stream entry '{}' has no field '{}'", entry.id, PAYLOAD_FIELD)))?;
+    codec::decode::<C, T>(&format!("stream entry '{}'", entry.id), &bytes)
+}
+
+impl Redis {
+    /// `XADD` an entry of fields, the stream trimmed to about `maxlen` entries when given
+    /// # Returns
+    /// * `String` - id of the entry
+    pub async fn xadd_fields<F: ToRedisArgs + Send + Sync, V: ToRedisArgs + Send + Sync>(&self, key: &str, fields: &[(F, V)], maxlen: Option<usize>) -> Facade<String> {
+        let mut conn = self.connection().await?;
+        let id: Option<String> = match maxlen {
+            Some(maxlen) => self.settle(conn.xadd_maxlen(self.key(key), StreamMaxlen::Approx(maxlen), "*", fields).await)?,
+            None => self.settle(conn.xadd(self.key(key), "*", fields).await)?,
+        };
+        id.ok_or(Erx::boxed(&format!("redis stream '{}' XADD returned no id", key)))
+    }
+
+    /// `XADD` a json entry, see `PAYLOAD_FIELD`
+    pub async fn xadd_json<T: Serialize>(&self, key: &str, value: &T, maxlen: Option<usize>) -> Facade<String> {
+        self.typed::<Json>().xadd(key, value, maxlen).await
+    }
+
+    /// consumer of a stream group, the group made from the start of the stream when missing,
+    /// the stream too. the consumer reads on a connection of its own, blocking reads hold no one else up
+    /// # Arguments
+    /// * `key` - stream key
+    /// * `group` - consumer group
+    /// * `name` - consumer name, unique in the group, e.g. the host name
+    pub async fn consumer(&self, key: &str, group: &str, name: &str) -> Facade<Consumer> {
+        let redis = Redis { trace: self.trace, client: self.client.clone(), connection: Arc::new(Mutex::new(None)), prefix: self.prefix.clone() };
+        let mut conn = redis.connection().await?;
+
+        let created: redis::RedisResult<()> = conn.xgroup_create_mkstream(redis.key(key), group, "0").await;
+        match created {
+            Err(ex) if ex.code() == Some("BUSYGROUP") => (),
+            created => redis.settle(created)?,
+        }
+
+        Ok(Consumer { redis, key: key.to_string(), group: group.to_string(), name: name.to_string() })
+    }
+}
+
+impl<C: Codec> Typed<'_, C> {
+    /// `XADD` an entry of the value encoded by `C`, see `PAYLOAD_FIELD`
+    pub async fn xadd<T: Serialize>(&self, key: &str, value: &T, maxlen: Option<usize>) -> Facade<String> {
+        let bytes = codec::encode::<C, T>(value)?;
+        self.redis.xadd_fields(key, &[(PAYLOAD_FIELD, bytes)], maxlen).await
+    }
+}
+
+/// Stream Consumer of a group
+/// # Fields
+/// * `redis` - facade over the connection of this consumer
+/// * `key` - stream key
+/// * `group` - consumer group
+/// * `name` - consumer name
+pub struct Consumer {
+    redis: Redis,
+    key: String,
+    group: String,
+    name: String,
+}
+
+impl Consumer {
+    /// consumer name
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// `XREADGROUP` entries never delivered to the group
+    /// # Arguments
+    /// * `count` - max entries
+    /// * `block` - wait that long for entries when none, `None` returns at once
+    pub async fn read(&self, count: usize, block: Option<Duration>) -> Facade<Vec<StreamId>> {
+        self.read_from(">", count, block).await
+    }
+
+    /// `XREADGROUP` entries delivered to this consumer and not acked yet, e.g. after a restart
+    pub async fn read_pending(&self, count: usize) -> Facade<Vec<StreamId>> {
+        self.read_from("0", count, None).await
+    }
+
+    async fn read_from(&self, id: &str, count: usize, block: Option<Duration>) -> Facade<Vec<StreamId>> {
+        let mut options = StreamReadOptions::default().group(&self.group, &self.name).count(count);
+        if let Some(block) = block {
+            options = options.block(block.as_millis() as usize);
+        }
+
+        let mut conn = self.redis.connection().await?;
+        let reply: Option<StreamReadReply> = self.redis.settle(conn.xread_options(&[self.redis.key(self.key.as_str())], &[id], &options).await)?;
+        Ok(reply.map(|reply| reply.keys.into_iter().flat_map(|key| key.ids).collect()).unwrap_or_default())
+    }
+
+    /// `XACK` handled entries
+    /// # Returns
+    /// * `i64` - entries acked
+    pub async fn ack(&self, ids: &[&str]) -> FacadeInt {
+        if ids.is_empty() {
+            return Ok(0);
+        }
+        let mut conn = self.redis.connection().await?;
+        self.redis.settle(conn.xack(self.redis.key(self.key.as_str()), &self.group, ids).await)
+    }
+
+    /// `XPENDING` entries of the group idle for `min_idle` at least, of any consumer
+    pub async fn pending(&self, min_idle: Duration, count: usize) -> Facade<Vec<StreamPendingId>> {
+        let mut conn = self.redis.connection().await?;
+        let reply: StreamPendingCountReply = self.redis.settle(
+            redis::cmd("XPENDING")
+                .arg(self.redis.key(self.key.as_str()))
+                .arg(&self.group)
+                .arg("IDLE")
+                .arg(min_idle.as_millis() as u64)
+                .arg("-")
+                .arg("+")
+                .arg(count)
+                .query_async(&mut conn)
+                .await,
+        )?;
+        Ok(reply.ids)
+    }
+
+    /// `XCLAIM` entries for this consumer, those idle for `min_idle` at least, others stay with their consumer
+    pub async fn claim(&self, min_idle: Duration, ids: &[&str]) -> Facade<Vec<StreamId>> {
+        if ids.is_empty() {
+            return Ok(vec![]);
+        }
+        let mut conn = self.redis.connection().await?;
+        let reply: StreamClaimReply = self.redis.settle(conn.xclaim(self.redis.key(self.key.as_str()), &self.group, &self.name, min_idle.as_millis() as u64, ids).await)?;
+        Ok(reply.ids)
+    }
+
+    /// claim entries other consumers left pending for `min_idle` at least, e.g. as they died
+    pub async fn claim_stuck(&self, min_idle: Duration, count: usize) -> Facade<Vec<StreamId>> {
+        let pending = self.pending(min_idle, count).await?;
+        let ids: Vec<&str> = pending.iter().filter(|p| p.consumer != self.name).map(|p| p.id.as_str()).collect();
+        if !ids.is_empty() {
+            tracing::info!("redis stream '{}' group '{}': {} claims {} stuck entries", self.key, self.group, self.name, ids.len());
+        }
+        self.claim(min_idle, &ids).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_decode() {
+        let entry = StreamId { id: "1-0".to_string(), map: HashMap::from([(PAYLOAD_FIELD.to_string(), redis::Value::BulkString(b"[1,2]".to_vec()))]) };
+        assert_eq!(decode::<Vec<i32>>(&entry).unwrap(), vec![1, 2]);
+        assert!(codec::is_decode_error(&decode::<String>(&entry).unwrap_err()));
+
+        let bare = StreamId { id: "2-0".to_string(), map: HashMap::new() };
+        assert!(!codec::is_decode_error(&decode::<Vec<i32>>(&bare).unwrap_err()));
+    }
+}
@@ -0,0 +1,234 @@
+//! Read/write splitting
+//!
+//! a database backend is a primary, its replicas declare `replica_of` (and are `readonly`):
+//! ```yaml
+//! model:
+//!   backends:
+//!     main:
+//!       kind: postgres
+//!       readonly: false
+//!       connect: "postgres://..."
+//!     main_r1:
+//!       kind: postgres
+//!       readonly: true
+//!       replica_of: main
+//!       weight: 2
+//!       connect: "postgres://..."
+//! ```
+//! finders read with `model::reader()`, the `{Name}Finder` of `seaorm_mo!` does, mutators and transactions use `model::writer()`:
+//! ```ignore
+//! CnregionEnt::find().all(&rings::model::reader()?).await
+//! ```
+//! `model::db(name)` and `model::shared()` refuse readonly backends, `Connections::readonly` reads them,
+//! `db_erx` keeps the code of a rejected mutation when converting database errors.
+
+use crate::erx::{simple_conv_boxed, Erx, Layouted, ResultBoxedE};
+use crate::tools::balanced::vector_gcd;
+use sea_orm::{ConnectionTrait, DatabaseConnection, DatabaseTransaction, DbBackend, DbErr, ExecResult, QueryResult, Statement, TransactionTrait};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// leading keywords of statements reading only
+const READ_KEYWORDS: [&str; 5] = ["select", "with", "show", "explain", "values"];
+
+/// keywords a read statement must not contain, e.g. `WITH x AS (...) DELETE ...`
+const MUTATE_KEYWORDS: [&str; 7] = ["insert", "update", "delete", "merge", "truncate", "drop", "alter"];
+
+/// the statement only reads
+pub fn is_read_sql(sql: &str) -> bool {
+    let lowered = sql.trim_start().to_lowercase();
+    let mut words = lowered.split(|c: char| !c.is_ascii_alphanumeric() && c != '_').filter(|w| !w.is_empty());
+
+    match words.next() {
+        Some(first) if READ_KEYWORDS.contains(&first) => !words.any(|w| MUTATE_KEYWORDS.contains(&w)),
+        _ => false,
+    }
+}
+
+/// code category of the errors of mutations sent to readonly backends
+pub const READONLY_CATEGORY: &str = "RDON";
+
+const READONLY_PREFIX: &str = "database backend '";
+
+const READONLY_SUFFIX: &str = "' is readonly, mutations are rejected";
+
+/// the error of a mutation sent to a readonly backend
+pub fn readonly_error(name: &str) -> Box<Erx> {
+    let mut erx = Erx::new(&format!("{}{}{}", READONLY_PREFIX, name, READONLY_SUFFIX));
+    *erx.code_mut() = Layouted::model(READONLY_CATEGORY, "EROR");
+    erx.to_boxed()
+}
+
+/// `Erx` of a database error, a mutation rejected by `Readonly` keeps the code of `readonly_error`
+pub fn db_erx(ex: DbErr) -> Box<Erx> {
+    if let DbErr::Custom(message) = &ex {
+        if let Some((name, "")) = message.strip_prefix(READONLY_PREFIX).and_then(|m| m.rsplit_once(READONLY_SUFFIX)) {
+            return readonly_error(name);
+        }
+    }
+    simple_conv_boxed(ex)
+}
+
+/// Readonly connection, statements other than reads are rejected
+/// # Fields
+/// * `name` - backend name
+/// * `connection` - the database connection
+#[derive(Clone)]
+pub struct Readonly {
+    name: String,
+    connection: &'static DatabaseConnection,
+}
+
+impl Readonly {
+    pub fn new(name: &str, connection: &'static DatabaseConnection) -> Self {
+        Readonly { name: name.to_string(), connection }
+    }
+
+    /// backend name
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// rejection of a mutation, `db_erx` turns it back into `readonly_error`
+    fn reject(&self, sql: &str) -> DbErr {
+        let error = readonly_error(&self.name);
+        tracing::error!("{}: {}", error.message(), sql);
+        DbErr::Custom(error.message_string())
+    }
+}
+
+#[async_trait::async_trait]
+impl ConnectionTrait for Readonly {
+    fn get_database_backend(&self) -> DbBackend {
+        self.connection.get_database_backend()
+    }
+
+    async fn execute(&self, stmt: Statement) -> Result<ExecResult, DbErr> {
+        Err(self.reject(&stmt.sql))
+    }
+
+    async fn execute_unprepared(&self, sql: &str) -> Result<ExecResult, DbErr> {
+        Err(self.reject(sql))
+    }
+
+    async fn query_one(&self, stmt: Statement) -> Result<Option<QueryResult>, DbErr> {
+        if !is_read_sql(&stmt.sql) {
+            return Err(self.reject(&stmt.sql));
+        }
+        self.connection.query_one(stmt).await
+    }
+
+    async fn query_all(&self, stmt: Statement) -> Result<Vec<QueryResult>, DbErr> {
+        if !is_read_sql(&stmt.sql) {
+            return Err(self.reject(&stmt.sql));
+        }
+        self.connection.query_all(stmt).await
+    }
+}
+
+/// weighted round-robin order of replica indexes, weights reduced by their gcd and interleaved,
+/// the same way `tools::balanced::Balanced` builds its pool
+fn weighted_pool(weights: &[u8]) -> Vec<usize> {
+    let mut weights: Vec<u8> = weights.to_vec();
+    if let Some(gcd @ 2..) = vector_gcd(&weights.iter().copied().filter(|w| *w > 0).collect::<Vec<u8>>()) {
+        weights.iter_mut().for_each(|w| *w /= gcd);
+    }
+
+    let mut pool = vec![];
+    while weights.iter().any(|w| *w > 0) {
+        for (index, weight) in weights.iter_mut().enumerate().filter(|(_, w)| **w > 0) {
+            pool.push(index);
+            *weight -= 1;
+        }
+    }
+    pool
+}
+
+/// Database Router, reads go to replicas, writes and transactions to the primary
+/// # Fields
+/// * `primary` - primary backend name
+/// * `writable` - primary is not a readonly backend
+/// * `connection` - primary connection
+/// * `readers` - replicas, or the primary when no replica
+/// * `pool` - weighted round-robin order of `readers`
+/// * `cursor` - reads routed so far
+pub struct Router {
+    primary: String,
+    writable: bool,
+    connection: &'static DatabaseConnection,
+    readers: Vec<Readonly>,
+    pool: Vec<usize>,
+    cursor: AtomicUsize,
+}
+
+impl Router {
+    /// # Arguments
+    /// * `primary` - primary backend name
+    /// * `readonly` - primary backend is readonly
+    /// * `connection` - primary connection
+    /// * `replicas` - replica backend name, connection and weight, replicas of weight 0 are not read
+    pub fn new(primary: &str, readonly: bool, connection: &'static DatabaseConnection, replicas: Vec<(String, &'static DatabaseConnection, u8)>) -> Self {
+        let weights: Vec<u8> = replicas.iter().map(|(_, _, weight)| *weight).collect();
+        let mut readers: Vec<Readonly> = replicas.iter().map(|(name, conn, _)| Readonly::new(name, conn)).collect();
+        let mut pool = weighted_pool(&weights);
+        if pool.is_empty() {
+            readers = vec![Readonly::new(primary, connection)];
+            pool = vec![0];
+        }
+
+        Router { primary: primary.to_string(), writable: !readonly, connection, readers, pool, cursor: AtomicUsize::new(0) }
+    }
+
+    /// primary backend name
+    pub fn primary(&self) -> &str {
+        &self.primary
+    }
+
+    /// names of the backends reads go to
+    pub fn reader_names(&self) -> Vec<&str> {
+        self.readers.iter().map(|r| r.name()).collect()
+    }
+
+    /// next reader in weighted round-robin
+    pub fn reader(&self) -> Readonly {
+        let at = self.cursor.fetch_add(1, Ordering::Relaxed) % self.pool.len();
+        self.readers[self.pool[at]].clone()
+    }
+
+    /// the primary connection, for writes
+    /// # Returns
+    /// * `Err` - the primary is a readonly backend
+    pub fn writer(&self) -> ResultBoxedE<&'static DatabaseConnection> {
+        if !self.writable {
+            return Err(readonly_error(&self.primary));
+        }
+        Ok(self.connection)
+    }
+
+    /// begin a transaction on the primary
+    pub async fn begin(&self) -> ResultBoxedE<DatabaseTransaction> {
+        self.writer()?.begin().await.map_err(crate::erx::simple_conv_boxed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_read_sql() {
+        assert!(is_read_sql("SELECT * FROM users"));
+        assert!(is_read_sql("  with t as (select 1) select * from t"));
+        assert!(!is_read_sql("INSERT INTO users (id) VALUES (1) RETURNING id"));
+        assert!(!is_read_sql("WITH gone AS (DELETE FROM users RETURNING id) SELECT * FROM gone"));
+        assert!(!is_read_sql("update users set name = 'x'"));
+        assert!(is_read_sql("select updated_at from users"));
+    }
+
+    #[test]
+    fn test_weighted_pool() {
+        assert_eq!(weighted_pool(&[1, 1, 1]), vec![0, 1, 2]);
+        assert_eq!(weighted_pool(&[4, 2]), vec![0, 1, 0]);
+        assert_eq!(weighted_pool(&[0, 3]), vec![1]);
+        assert!(weighted_pool(&[0]).is_empty());
+    }
+}
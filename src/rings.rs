@@ -1,12 +1,21 @@
+pub mod event;
+pub mod supervise;
+pub mod timeline;
+
 use crate::core::traits::any::AnyTrait;
-use crate::erx::{simple_conv_boxed, ResultBoxedE, ResultBoxedEX};
+use crate::erx::{Erx, ResultBoxedE, ResultBoxedEX};
+use crate::model::Connections;
+use crate::service::ServiceManager;
+use event::{RingsEvent, RingsEventKind, RingsEvents};
+use supervise::{RestartPolicy, Supervision};
+use timeline::{Timeline, MOMENT_FIRE, MOMENT_MAKE};
 use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{HashMap, VecDeque};
 use std::str::FromStr;
 use std::sync::{Arc, OnceLock};
-use tokio::sync::RwLock;
-use tracing::{error, info, span};
+use tokio::sync::{broadcast, watch, RwLock};
+use tracing::{error, info, span, warn};
 
 /// Rings Application
 /// RingsApplication = Arc<RwLock<Rings>>
@@ -15,6 +24,27 @@ pub type RingsApplication = Arc<RwLock<Rings>>;
 /// Rings
 static _RINGS: OnceLock<RwLock<Vec<RingsApplication>>> = OnceLock::new();
 
+/// Name of the first made rings application, used by the `shared` accessors
+static _DEFAULT_RINGS_NAME: OnceLock<String> = OnceLock::new();
+
+/// Name of the rings application last made, the one being built
+static _BUILDING_RINGS_NAME: std::sync::RwLock<Option<String>> = std::sync::RwLock::new(None);
+
+/// Fallback name used by the `shared` accessors before any rings application is made
+pub const DEFAULT_RINGS_NAME: &str = "default";
+
+/// max time to wait a fired mod become ready
+const MOD_READY_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(30);
+
+/// max time to wait a mod terminated after shutdown signaled
+const MOD_TERMINATE_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(10);
+
+/// interval of mod stage checking, for mods without `stage_watch`
+const MOD_STAGE_POLL: std::time::Duration = std::time::Duration::from_millis(50);
+
+/// max interval between two supervision rounds, for mods without `stage_watch`
+const SUPERVISE_INTERVAL: std::time::Duration = std::time::Duration::from_secs(1);
+
 #[allow(clippy::type_complexity)]
 static RINGS_INVOKE_MACRO: std::sync::RwLock<Vec<(String, fn())>> = std::sync::RwLock::new(Vec::new());
 
@@ -22,6 +52,28 @@ fn ring_apps() -> &'static RwLock<Vec<RingsApplication>> {
     _RINGS.get_or_init(|| RwLock::new(vec![]))
 }
 
+/// name of the default rings application
+/// the first application made by `R::make` becomes the default one,
+/// `ServiceManager::shared()` and `model::shared()` resolve against it.
+pub fn default_rings_name() -> &'static str {
+    _DEFAULT_RINGS_NAME.get().map(|s| s.as_str()).unwrap_or(DEFAULT_RINGS_NAME)
+}
+
+/// name of the rings application being built, the one last made by `R::make`,
+/// the default one before any is made. `hey_service!` registers services to it.
+pub fn building_rings_name() -> String {
+    match _BUILDING_RINGS_NAME.read().ok().and_then(|name| name.clone()) {
+        Some(name) => name,
+        None => default_rings_name().to_string(),
+    }
+}
+
+fn set_building_rings_name(name: &str) {
+    if let Ok(mut building) = _BUILDING_RINGS_NAME.write() {
+        *building = Some(name.to_string());
+    }
+}
+
 /// name: ringsapp name
 pub fn add_rings_invoke_macro(name: &str, func: fn()) {
     RINGS_INVOKE_MACRO.write().unwrap().push((name.to_string(), func));
@@ -34,25 +86,68 @@ pub struct Rings {
     /// Rings Mods
     mods: Vec<Box<dyn RingsMod>>,
     /// Rings State
-    state: Arc<RwLock<RingState>>,
+    state: SafeRingState,
     /// Moments
     moments: Vec<Moment>,
+    /// Services owned by this application
+    services: &'static ServiceManager,
+    /// Model connections owned by this application
+    models: &'static Connections,
+    /// Hooks invoked on reload
+    reload_hooks: Vec<ReloadHook>,
+    /// Lifecycle events
+    events: RingsEvents,
+    /// Supervision records of failed mods, by mod name
+    supervisions: HashMap<String, Supervision>,
 }
 
+/// Reload hook, invoked when rings application reloading (SIGHUP)
+pub type ReloadHook = Arc<dyn Fn() -> futures_util::future::BoxFuture<'static, ResultBoxedEX> + Send + Sync>;
+
 /// Moment is a moment in time.
+/// a moment with `elapsed` is a step began at `time` and took `elapsed` micros.
 #[derive(Deserialize, Serialize, Clone, Debug)]
 pub struct Moment {
     name: String,
     time: i64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    elapsed: Option<i64>,
 }
 
 impl Moment {
     /// Moment with current time
     pub fn now(name: &str) -> Self {
-        Self { name: name.to_string(), time: chrono::Utc::now().timestamp_micros() }
+        Self { name: name.to_string(), time: chrono::Utc::now().timestamp_micros(), elapsed: None }
+    }
+
+    /// Moment of a step began at `begin` (micros) and ending now
+    pub fn since(name: &str, begin: i64) -> Self {
+        Self { name: name.to_string(), time: begin, elapsed: Some(chrono::Utc::now().timestamp_micros() - begin) }
+    }
+
+    /// Moment of exact time (micros) and elapsed
+    pub fn of(name: &str, time: i64, elapsed: Option<i64>) -> Self {
+        Self { name: name.to_string(), time, elapsed }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn time(&self) -> i64 {
+        self.time
+    }
+
+    pub fn elapsed(&self) -> Option<i64> {
+        self.elapsed
     }
 }
 
+/// current time in micros, the begin of a `Moment::since`
+pub fn moment_begin() -> i64 {
+    chrono::Utc::now().timestamp_micros()
+}
+
 /// Rings State
 /// RingState::Init => 1,
 /// RingState::Ready => 10,
@@ -60,6 +155,7 @@ impl Moment {
 /// RingState::Paused => 9999,
 /// RingState::Terminating => -10,
 /// RingState::Terminated => -1,
+/// RingState::Failed => -100,
 /// RingState::Unknown => 0,
 #[derive(Debug, Ord, PartialOrd, Eq, PartialEq, Copy, Clone, Serialize, Deserialize)]
 pub enum RingState {
@@ -69,12 +165,68 @@ pub enum RingState {
     Paused,
     Terminating,
     Terminated,
+    Failed,
     Unknown,
 }
 
 /// Ring Thread Safe State
-/// SafeRingState = Arc<RwLock<RingState>>
-pub type SafeRingState = Arc<RwLock<RingState>>;
+/// backed by a `tokio::sync::watch` channel, every change wakes up the waiters immediately,
+/// clones share the same state.
+#[derive(Clone, Debug)]
+pub struct SafeRingState(Arc<watch::Sender<RingState>>);
+
+impl SafeRingState {
+    pub fn new(s: RingState) -> Self {
+        SafeRingState(Arc::new(watch::Sender::new(s)))
+    }
+
+    /// current state
+    pub fn get(&self) -> RingState {
+        *self.0.borrow()
+    }
+
+    /// set state and notify the waiters
+    pub fn set(&self, s: RingState) {
+        self.0.send_replace(s);
+    }
+
+    /// set state to `to` only if current state is `expected`
+    /// returns the current state as error if not matched
+    pub fn compare_and_set(&self, expected: RingState, to: RingState) -> Result<(), RingState> {
+        let mut current = expected;
+        self.0.send_if_modified(|s| {
+            current = *s;
+            if *s != expected {
+                return false;
+            }
+            *s = to;
+            true
+        });
+
+        if current == expected {
+            Ok(())
+        } else {
+            Err(current)
+        }
+    }
+
+    /// receiver of state changes
+    pub fn subscribe(&self) -> watch::Receiver<RingState> {
+        self.0.subscribe()
+    }
+
+    /// wait until state satisfies `pred`, returns the matched state
+    pub async fn wait_for(&self, pred: impl FnMut(&RingState) -> bool) -> RingState {
+        let mut rx = self.subscribe();
+        let matched = rx.wait_for(pred).await.map(|s| *s);
+        matched.unwrap_or(RingState::Unknown)
+    }
+
+    /// wait until state is `Terminating` or `Terminated`
+    pub async fn wait_terminating(&self) -> RingState {
+        self.wait_for(|s| matches!(s, RingState::Terminating | RingState::Terminated)).await
+    }
+}
 
 // impl std::fmt::Display for RingState {
 //     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {}
@@ -89,6 +241,7 @@ impl From<RingState> for i32 {
             RingState::Paused => 9999,
             RingState::Terminating => -10,
             RingState::Terminated => -1,
+            RingState::Failed => -100,
             RingState::Unknown => 0,
         }
     }
@@ -103,6 +256,7 @@ impl From<i32> for RingState {
             9999 => RingState::Paused,
             -10 => RingState::Terminating,
             -1 => RingState::Terminated,
+            -100 => RingState::Failed,
             _ => RingState::Unknown,
         }
     }
@@ -119,6 +273,7 @@ impl FromStr for RingState {
             "paused" => Ok(RingState::Paused),
             "terminating" => Ok(RingState::Terminating),
             "terminated" => Ok(RingState::Terminated),
+            "failed" => Ok(RingState::Failed),
             _ => Err(format!("Unknown ring state: {}", s)),
         }
     }
@@ -133,6 +288,7 @@ impl From<RingState> for &str {
             RingState::Paused => "paused",
             RingState::Terminating => "terminating",
             RingState::Terminated => "terminated",
+            RingState::Failed => "failed",
             RingState::Unknown => "unknown",
         }
     }
@@ -144,26 +300,25 @@ impl RingState {
     }
 
     pub fn safe_ring_state_set(rs: &SafeRingState, s: RingState) -> ResultBoxedEX {
-        *rs.try_write().map_err(simple_conv_boxed)? = s;
+        rs.set(s);
         Ok(())
     }
 
     pub async fn safe_ring_state_must_set(rs: &SafeRingState, s: RingState) -> ResultBoxedEX {
-        let mut g = rs.write().await;
-        *g = s;
+        rs.set(s);
         Ok(())
     }
 
     pub fn safe_ring_state_get(rs: &SafeRingState) -> ResultBoxedE<RingState> {
-        Ok(*rs.try_read().map_err(simple_conv_boxed)?)
+        Ok(rs.get())
     }
 
     pub async fn safe_ring_state_must_get(rs: &SafeRingState) -> ResultBoxedE<RingState> {
-        Ok(*rs.read().await)
+        Ok(rs.get())
     }
 
     pub fn inited_safe_ring_state() -> SafeRingState {
-        Arc::new(RwLock::new(RingState::Init))
+        SafeRingState::new(RingState::Init)
     }
 }
 
@@ -177,6 +332,44 @@ pub trait RingsMod: AnyTrait + Send + Sync {
     async fn fire(&mut self) -> ResultBoxedEX;
     async fn stage(&self) -> RingState;
     fn level(&self) -> i64;
+
+    /// receiver of stage changes, lets rings await transitions instead of polling `stage`,
+    /// and emit them as `StateChanged` events of the mod
+    fn stage_watch(&self) -> Option<watch::Receiver<RingState>> {
+        None
+    }
+
+    /// names of mods that must be fired before this one, and shut down after it
+    fn dependencies(&self) -> Vec<String> {
+        vec![]
+    }
+
+    /// time allowed to reach `Terminated` after shutdown signaled,
+    /// `shutdown.mods.<name>` in config takes precedence
+    fn shutdown_deadline(&self) -> Option<std::time::Duration> {
+        None
+    }
+
+    /// what to do when the mod turns `Failed` after fired,
+    /// mods report failures of their background tasks with `supervise::spawn_supervised`
+    fn restart_policy(&self) -> RestartPolicy {
+        RestartPolicy::Never
+    }
+
+    /// stop immediately, called when not terminated before the shutdown deadline
+    async fn force_stop(&mut self) -> ResultBoxedEX {
+        Ok(())
+    }
+
+    /// pause, keep resources but stop working until resumed
+    async fn pause(&mut self) -> ResultBoxedEX {
+        Err(Erx::boxed(&format!("mod:[ {} ] does not support pause", self.name())))
+    }
+
+    /// resume from paused
+    async fn resume(&mut self) -> ResultBoxedEX {
+        Err(Erx::boxed(&format!("mod:[ {} ] does not support resume", self.name())))
+    }
 }
 
 /// R
@@ -194,62 +387,39 @@ impl R {
     }
 
     // make rings
+    // every application owns its own service manager and model connections,
+    // making an application with an already registered name returns the existing one.
     pub async fn make(name: &str) -> RingsApplication {
         crate::log::logging_initialize().await;
 
+        let mut rings = ring_apps().write().await;
+        for ring in rings.iter() {
+            if ring.read().await.name.eq(name) {
+                warn!("rings application:{} already made, reuse it", name);
+                set_building_rings_name(name);
+                return Arc::clone(ring);
+            }
+        }
+
         let app = Rings {
             name: name.to_string(),
             mods: vec![],
-            state: Arc::new(RwLock::new(RingState::Init)),
-            moments: vec![Moment::now("make")],
+            state: RingState::inited_safe_ring_state(),
+            moments: vec![Moment::now(MOMENT_MAKE)],
+            services: ServiceManager::make(name),
+            models: crate::model::connections(name),
+            reload_hooks: vec![],
+            events: RingsEvents::new(name),
+            supervisions: HashMap::new(),
         };
 
-        // app.register_mod(SchedulerManager::new()).await;
-
         let arc: RingsApplication = Arc::new(RwLock::new(app));
+        rings.push(Arc::clone(&arc));
+        drop(rings);
 
-        // match ring_apps().try_write() {
-        //     Ok(mut rings) => {
-        //         // # TODO
-        //         // Currently, only one app registration is supported.
-        //         // When we change this later, we need to synchronously modify
-        //         // the support for multiple RingApps in other components
-        //         // like ServiceManager, SchedulerManager, and Model.
-        //         if rings.len() > 1 {
-        //             panic!(
-        //                 "Sorry, you've already registered an app. \
-        //             The current version only supports registering one app. \
-        //             We'll support multiple apps as soon as possible."
-        //             );
-        //         }
-
-        //         rings.push(Arc::clone(&arc));
-        //     },
-        //     Err(ex) => {
-        //         error!("make rings push RINGS: {}", ex);
-        //     },
-        // }
-
-        match ring_apps().try_write() {
-            Ok(mut rings) => {
-                // # TODO
-                // Currently, only one app registration is supported.
-                // When we change this later, we need to synchronously modify
-                // the support for multiple RingApps in other components
-                // like ServiceManager, SchedulerManager, and Model.
-                if rings.len() > 1 {
-                    panic!(
-                        "Sorry, you've already registered an app. \
-                    The current version only supports registering one app. \
-                    We'll support multiple apps as soon as possible."
-                    );
-                }
-
-                rings.push(Arc::clone(&arc));
-            },
-            Err(ex) => {
-                error!("make rings push RINGS: {}", ex);
-            },
+        set_building_rings_name(name);
+        if _DEFAULT_RINGS_NAME.set(name.to_string()).is_ok() {
+            info!("rings application:{} is the default application", name);
         }
 
         info!("rings application:{} made", name);
@@ -264,25 +434,79 @@ impl R {
         Arc::clone(&arc)
     }
 
+    /// names of all made rings applications
+    pub async fn names() -> Vec<String> {
+        let mut names = vec![];
+        for ring in ring_apps().read().await.iter() {
+            names.push(ring.read().await.name.clone());
+        }
+        names
+    }
+
     pub async fn perform(rings_app: &RingsApplication) {
-        match rings_app.try_write() {
-            Ok(mut guard) => {
-                guard.fire().await;
-            },
-            Err(ex) => {
-                error!("{}", ex);
-            },
-        };
+        let fired = rings_app.write().await.fire().await;
+        if let Err(ex) = fired {
+            error!("rings fire failed: {}", ex.message());
+            error!("{}", rings_app.read().await.timeline().summary());
+            rings_app.write().await.shutdown().await;
+            return;
+        }
+
+        info!("{}", rings_app.read().await.timeline().summary());
 
         Rings::serve(rings_app).await;
     }
 }
 
 impl Rings {
+    /// rings application name
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// service manager owned by this application
+    pub fn services(&self) -> &'static ServiceManager {
+        self.services
+    }
+
+    /// model connections owned by this application
+    pub fn models(&self) -> &'static Connections {
+        self.models
+    }
+
+    /// subscribe lifecycle events of this application and its mods
+    pub fn subscribe(&self) -> broadcast::Receiver<RingsEvent> {
+        self.events.subscribe()
+    }
+
+    /// lifecycle events handle, emit from outside of rings (e.g. spawned tasks)
+    pub fn events(&self) -> RingsEvents {
+        self.events.clone()
+    }
+
+    /// set application state, emits `StateChanged` when changed
+    fn transit(&self, to: RingState) {
+        let from = self.state.get();
+        self.state.set(to);
+        if from != to {
+            self.events.emit(RingsEventKind::StateChanged { from, to });
+        }
+    }
+
     pub fn make_moment(&mut self, name: &str) {
         self.moments.push(Moment::now(name));
     }
 
+    /// record a moment, e.g. `Moment::since` of a step done outside of rings
+    pub fn add_moment(&mut self, moment: Moment) {
+        self.moments.push(moment);
+    }
+
+    /// startup timeline built from moments
+    pub fn timeline(&self) -> Timeline {
+        Timeline::from_moments(&self.name, &self.moments)
+    }
+
     pub fn get_moments(&self, pred: Option<String>, after: Option<i64>) -> Vec<Moment> {
         let mut moments: Vec<Moment> = self.moments.clone();
         if let Some(pred) = pred {
@@ -300,9 +524,22 @@ impl Rings {
             return self;
         }
 
-        md.initialize().await.expect("initialize mod failed.");
+        let name = md.name();
+        self.events.emit_mod(&name, RingsEventKind::Registered);
+
+        let begin = moment_begin();
+        if let Err(ex) = md.initialize().await {
+            self.events.emit_mod(&name, RingsEventKind::Failed { error: ex.message_string() });
+            panic!("initialize mod:[ {} ] failed: {}", name, ex.message());
+        }
+        self.moments.push(Moment::since(&format!("mod [{}] initialize", name), begin));
+        self.events.emit_mod(&name, RingsEventKind::Initialized);
+
+        if let Some(stage) = md.stage_watch() {
+            self.events.relay_mod_stages(&name, stage);
+        }
         self.mods.push(Box::new(md));
-        self.moments.push(Moment::now(&format!("mod [{}] registered", &self.name)));
+        self.moments.push(Moment::now(&format!("mod [{}] registered", name)));
 
         // self.mods.sort_by(|a, b| a.level().cmp(&b.level()));
         self.mods.sort_by_key(|a| a.level());
@@ -310,24 +547,226 @@ impl Rings {
         self
     }
 
+    /// plug a mod into the application at any time.
+    /// once the application fired, the mod is fired immediately and waited ready (and paused if the application paused),
+    /// before that it is only registered and fired with the others.
+    /// a mod failed to initialize, resolve its dependencies or fire is not kept.
+    pub async fn plug_mod<T: RingsMod>(&mut self, mut md: T) -> ResultBoxedEX {
+        let name = md.name();
+        let current = self.state.get();
+        if !matches!(current, RingState::Init | RingState::Ready | RingState::Working | RingState::Paused) {
+            let current: &str = current.into();
+            return Err(Erx::boxed(&format!("rings application:{} current state:{} can not plug mod:[ {} ]", self.name, current, name)));
+        }
+
+        if !md.duplicate_able() && self.mods.iter().any(|x| x.name().eq(&name)) {
+            return Err(Erx::boxed(&format!("mod:[ {} ] already registered", name)));
+        }
+
+        self.events.emit_mod(&name, RingsEventKind::Registered);
+
+        let begin = moment_begin();
+        if let Err(ex) = md.initialize().await {
+            self.events.emit_mod(&name, RingsEventKind::Failed { error: ex.message_string() });
+            return Err(Erx::boxed(&format!("initialize mod:[ {} ] failed: {}", name, ex.message())));
+        }
+        self.moments.push(Moment::since(&format!("mod [{}] initialize", name), begin));
+        self.events.emit_mod(&name, RingsEventKind::Initialized);
+
+        if let Some(stage) = md.stage_watch() {
+            self.events.relay_mod_stages(&name, stage);
+        }
+        self.mods.push(Box::new(md));
+        if let Err(ex) = self.fire_stages() {
+            self.events.emit_mod(&name, RingsEventKind::Failed { error: ex.message_string() });
+            if let Some(mut md) = self.mods.pop() {
+                let _ = md.unregister().await;
+            }
+            return Err(ex);
+        }
+
+        self.make_moment(&format!("mod [{}] plugged", name));
+
+        if matches!(current, RingState::Working | RingState::Paused) {
+            let md = self.mods.last_mut().expect("plugged mod");
+            let begin = moment_begin();
+            let fired = match self.events.firing(md.fire()).await {
+                Ok(_) => Self::wait_mod_ready(md.as_ref()).await,
+                Err(ex) => Err(ex),
+            };
+
+            if let Err(ex) = fired {
+                error!("plug mod:[ {} ] fire failed: {}", name, ex.message());
+                self.events.emit_mod(&name, RingsEventKind::Failed { error: ex.message_string() });
+                if let Some(mut md) = self.mods.pop() {
+                    let _ = md.force_stop().await;
+                }
+                return Err(Erx::boxed(&format!("fire mod:[ {} ] failed: {}", name, ex.message())));
+            }
+
+            info!("rings mod:[ {} ] plugged and fired", name);
+            self.moments.push(Moment::since(&format!("mod [{}] fire", name), begin));
+            self.events.emit_mod(&name, RingsEventKind::Fired);
+
+            if current == RingState::Paused {
+                if let Err(ex) = md.pause().await {
+                    warn!("rings mod:[ {} ] not paused: {}", name, ex.message());
+                }
+            }
+        }
+
+        self.mods.sort_by_key(|a| a.level());
+
+        Ok(())
+    }
+
+    /// shutdown all mods in reverse fire order.
+    /// every stage waits its mods terminated within their deadlines, bounded by the overall deadline
+    /// (`shutdown.deadline` and `shutdown.mods` in config), mods still alive after that are force stopped.
     pub async fn shutdown(&mut self) {
-        if !self.state.read().await.is_ready_to_terminating() {
+        if !self.state.get().is_ready_to_terminating() {
             return;
         }
 
         self.make_moment("shutdown");
+        let begin = moment_begin();
 
         info!("rings::shutdown....");
-        *self.state.write().await = RingState::Terminating;
+        self.events.emit(RingsEventKind::ShutdownRequested);
+        self.transit(RingState::Terminating);
+
+        let conf = crate::conf::rebit().read().await.shutdown.clone().unwrap_or_default();
+        let deadline = tokio::time::Instant::now() + conf.deadline();
+
+        let stages = self.fire_stages().unwrap_or_else(|ex| {
+            warn!("unable to resolve mods order: {}, shutdown all at once", ex.message());
+            vec![(0..self.mods.len()).collect()]
+        });
+
+        for stage in stages.iter().rev() {
+            for i in stage {
+                let md = &mut self.mods[*i];
+                if md.stage().await == RingState::Failed {
+                    warn!("rings mod:[ {} ] failed, skip shutdown", md.name());
+                    continue;
+                }
 
-        for md in self.mods.iter_mut() {
-            match md.shutdown().await {
-                Ok(_) => {
-                    info!("rings mod:[ {} ] shutdown accepted", md.name());
-                },
-                Err(ex) => {
-                    error!("failed to signal shutdown: {} error: {}", md.name(), ex.message());
-                },
+                // a hanging shutdown must not outlive the deadline, the mod is force stopped after waiting then
+                let timeout = Self::mod_shutdown_timeout(&conf, md.as_ref(), deadline);
+                match tokio::time::timeout(timeout, md.shutdown()).await {
+                    Ok(Ok(_)) => {
+                        info!("rings mod:[ {} ] shutdown accepted", md.name());
+                    },
+                    Ok(Err(ex)) => {
+                        error!("failed to signal shutdown: {} error: {}", md.name(), ex.message());
+                    },
+                    Err(_) => {
+                        error!("rings mod:[ {} ] shutdown not returned before deadline", md.name());
+                    },
+                }
+            }
+
+            let mods = &self.mods;
+            let conf = &conf;
+            let waits = stage.iter().map(|i| async move {
+                let md = mods[*i].as_ref();
+                (*i, Self::wait_mod_terminated(md, Self::mod_shutdown_timeout(conf, md, deadline)).await)
+            });
+
+            for (i, terminated) in futures_util::future::join_all(waits).await {
+                let md = &mut self.mods[i];
+                if terminated {
+                    if md.stage().await == RingState::Terminated {
+                        self.events.emit_mod(&md.name(), RingsEventKind::Terminated);
+                    }
+                    continue;
+                }
+
+                error!("rings mod:[ {} ] not terminated before deadline, force stopping", md.name());
+                let error = "not terminated before deadline, force stopped".to_string();
+                self.events.emit_mod(&md.name(), RingsEventKind::Failed { error });
+                if let Err(ex) = md.force_stop().await {
+                    error!("failed to force stop: {} error: {}", md.name(), ex.message());
+                }
+            }
+        }
+
+        self.add_moment(Moment::since("terminated", begin));
+        self.transit(RingState::Terminated);
+        self.events.emit(RingsEventKind::Terminated);
+    }
+
+    /// pause all mods in reverse fire order, mods not supporting pause keep working
+    pub async fn pause(&mut self) -> ResultBoxedEX {
+        let current = self.state.get();
+        if current != RingState::Working {
+            let current: &str = current.into();
+            return Err(Erx::boxed(&format!("rings application:{} current state:{} can not pause", self.name, current)));
+        }
+
+        self.make_moment("pause");
+
+        let stages = self.fire_stages()?;
+        for stage in stages.iter().rev() {
+            for i in stage {
+                let md = &mut self.mods[*i];
+                match md.pause().await {
+                    Ok(_) => info!("rings mod:[ {} ] paused", md.name()),
+                    Err(ex) => warn!("rings mod:[ {} ] not paused: {}", md.name(), ex.message()),
+                }
+            }
+        }
+
+        self.transit(RingState::Paused);
+        Ok(())
+    }
+
+    /// resume all mods in fire order
+    pub async fn resume(&mut self) -> ResultBoxedEX {
+        let current = self.state.get();
+        if current != RingState::Paused {
+            let current: &str = current.into();
+            return Err(Erx::boxed(&format!("rings application:{} current state:{} can not resume", self.name, current)));
+        }
+
+        self.make_moment("resume");
+
+        let stages = self.fire_stages()?;
+        for stage in stages.iter() {
+            for i in stage {
+                let md = &mut self.mods[*i];
+                if md.stage().await != RingState::Paused {
+                    continue;
+                }
+
+                match md.resume().await {
+                    Ok(_) => info!("rings mod:[ {} ] resumed", md.name()),
+                    Err(ex) => error!("rings mod:[ {} ] resume failed: {}", md.name(), ex.message()),
+                }
+            }
+        }
+
+        self.transit(RingState::Working);
+        Ok(())
+    }
+
+    /// register a hook invoked on reload, e.g. when SIGHUP received
+    pub fn on_reload(&mut self, hook: ReloadHook) -> &mut Self {
+        self.reload_hooks.push(hook);
+        self
+    }
+
+    /// reload config, then invoke all reload hooks.
+    /// a config failing to reload keeps the previous one, hooks still run.
+    pub async fn reload(&self) {
+        info!("rings application:{} reloading, hooks: {}", self.name, self.reload_hooks.len());
+        if let Err(ex) = crate::conf::reload().await {
+            error!("rings application:{} reload config error: {}", self.name, ex.message());
+        }
+
+        for hook in self.reload_hooks.iter() {
+            if let Err(ex) = hook().await {
+                error!("rings application:{} reload hook error: {}", self.name, ex.message());
             }
         }
     }
@@ -352,97 +791,451 @@ impl Rings {
         None
     }
 
-    pub async fn remove_mod(&mut self, name: &str) -> &mut Self {
-        // let drain = |m: &dyn RingsMod| m.name().eq(name);
-        for m in &mut self.mods {
-            if name.eq(&m.name()) {
-                m.unregister().await.expect("unregister mod failed.");
+    /// remove mods by name, the application keeps running.
+    /// every removed mod is unregistered and awaited `Terminated` within its shutdown deadline,
+    /// then force stopped if still alive. fails when a remaining mod depends on it.
+    pub async fn remove_mod(&mut self, name: &str) -> ResultBoxedEX {
+        if let Some(supervision) = self.supervisions.get_mut(name).filter(|s| s.restarting) {
+            supervision.removed = true;
+            self.make_moment(&format!("mod [{}] removed", name));
+            return Ok(());
+        }
+
+        if !self.mods.iter().any(|m| name.eq(&m.name())) {
+            return Err(Erx::boxed(&format!("mod:[ {} ] not registered", name)));
+        }
+
+        let dependents: Vec<String> =
+            self.mods.iter().filter(|m| !name.eq(&m.name()) && m.dependencies().iter().any(|d| d.eq(name))).map(|m| m.name()).collect();
+        if !dependents.is_empty() {
+            return Err(Erx::boxed(&format!("mod:[ {} ] is depended on by: [ {} ]", name, dependents.join(", "))));
+        }
+
+        let conf = crate::conf::rebit().read().await.shutdown.clone().unwrap_or_default();
+        for md in self.mods.iter_mut().filter(|m| name.eq(&m.name())) {
+            // a mod never fired, or already ended, has nothing to wait for
+            let fired = !matches!(md.stage().await, RingState::Init | RingState::Terminated | RingState::Failed);
+            if let Err(ex) = md.unregister().await {
+                warn!("unregister mod:[ {} ] error: {}", name, ex.message());
+            }
+
+            let timeout = if fired {
+                conf.mod_deadline(name).or(md.shutdown_deadline()).unwrap_or(MOD_TERMINATE_TIMEOUT)
+            } else {
+                std::time::Duration::ZERO
+            };
+            if !Self::wait_mod_terminated(md.as_ref(), timeout).await {
+                if fired {
+                    error!("rings mod:[ {} ] not terminated in {:?}, force stopping", name, timeout);
+                }
+                if let Err(ex) = md.force_stop().await {
+                    error!("failed to force stop: {} error: {}", name, ex.message());
+                }
             }
+
+            self.events.emit_mod(name, RingsEventKind::Terminated);
         }
 
         self.mods.retain(|m| !name.eq(&m.name()));
+        self.supervisions.remove(name);
+        self.make_moment(&format!("mod [{}] removed", name));
 
-        self
+        Ok(())
     }
 
     pub fn get_state(&self) -> ResultBoxedE<RingState> {
-        Ok(*self.state.try_read().map_err(simple_conv_boxed)?)
+        Ok(self.state.get())
     }
 
     pub fn get_state_unchecked(&self) -> RingState {
-        match self.state.try_read() {
-            Ok(state) => *state,
-            Err(_) => RingState::Unknown,
-        }
+        self.state.get()
+    }
+
+    /// state handle, await transitions with `SafeRingState::wait_for`
+    pub fn state(&self) -> SafeRingState {
+        self.state.clone()
     }
 
-    pub async fn fire(&mut self) {
+    /// fire all mods, stage by stage
+    /// mods in the same stage are fired concurrently, the next stage starts
+    /// only when every mod of the current stage is ready.
+    /// returns error on dependency cycle, missing dependency, fire failure or readiness timeout.
+    pub async fn fire(&mut self) -> ResultBoxedEX {
         let span = span!(tracing::Level::INFO, "FireMod");
         let _guard = span.enter();
 
-        info!("Fire Rings, Mods: {}", self.mods.len());
+        let stages = self.fire_stages()?;
+        info!("Fire Rings, Mods: {}, Stages: {}", self.mods.len(), stages.len());
+
+        let begin = moment_begin();
+        for (index, stage) in stages.iter().enumerate() {
+            let stage_begin = moment_begin();
+            let events = &self.events;
+            let fires = self.mods.iter_mut().enumerate().filter(|(i, _)| stage.contains(i)).map(|(_, m)| async move {
+                let fired = events.firing(m.fire()).await;
+                (m.name(), m.level(), fired)
+            });
+
+            for (name, level, fired) in futures_util::future::join_all(fires).await {
+                match fired {
+                    Ok(_) => info!("fire stage {} level {} mod:[ {} ] success.", index, level, name),
+                    Err(e) => {
+                        error!("fire stage {} level {} mod:[ {} ] error:{}", index, level, name, e.message());
+                        self.events.emit_mod(&name, RingsEventKind::Failed { error: e.message_string() });
+                        return Err(Erx::boxed(&format!("fire mod:[ {} ] failed: {}", name, e.message())));
+                    },
+                }
+            }
 
-        for m in self.mods.iter_mut() {
-            if let Err(e) = m.fire().await {
-                error!("fire level {} mod:[ {} ] error:{}", m.level(), m.name(), e.message());
-            } else {
-                info!("fire level {} mod:[ {} ] success.", m.level(), m.name());
-            }
-        }
-
-        *self.state.write().await = RingState::Working;
-
-        // let mut groups: HashMap<i64, Vec<_>> = HashMap::new();
-        // for m in self.mods.iter_mut() {
-        //     groups.entry(m.level()).or_default().push(m.fire());
-        // }
-        //
-        // let mut levels: Vec<i64> = groups.keys().cloned().collect();
-        // for level in levels {
-        //     let futures = groups.get(&level).unwrap();
-        //     if !futures.is_empty() {
-        //         let fu = futures.iter().map(|x| *x).collect::<Vec<_>>();
-        //         futures_util::future::join_all(fu).await;
-        //     }
-        // }
-
-        // let mut groups = vec![];
-        // for m in self.mods.iter_mut() {
-        //     groups.push((m.level(), m.fire()));
-        //
-        //     let m = (m.level(), m.fire());
-        //
-        //
-        //     let s = m.fire();
-        //     info!("fire mod:[ {} ]", m.name());
-        //
-        //     match m.fire().await {
-        //         Err(ex) => {
-        //             error!("failed to fire mod: {} error: {}", m.name(), ex.message());
-        //         }
-        //         _ => {}
-        //     }
-        // }
-
-        // let groups: Vec<(i64, _)> = self.mods.iter_mut().map(|m| (m.level(), m.fire())).collect();
-
-        // let ctrl_c = |name: String, stage: Arc<RwLock<RingState>>| async move {
-        //     tokio::signal::ctrl_c().await.expect("RINGS attempt to terminate immediately");
-        //     *stage.write().unwrap() = RingState::Terminating;
-        //     tracing::info!("RINGS:{} received shutdown signal, set state: [Terminating]", name);
-        // };
-        //
-        // tokio::spawn(ctrl_c(self.name.clone(), Arc::clone(&self.state)));
+            let mods = &self.mods;
+            let waits = stage.iter().map(|i| async move {
+                let ready = Self::wait_mod_ready(mods[*i].as_ref()).await;
+                (ready, moment_begin())
+            });
+            let waited = futures_util::future::join_all(waits).await;
+            for (i, (ready, ready_at)) in stage.iter().zip(waited) {
+                let name = self.mods[*i].name();
+                if let Err(ex) = ready {
+                    self.events.emit_mod(&name, RingsEventKind::Failed { error: ex.message_string() });
+                    return Err(ex);
+                }
+                self.moments.push(Moment::of(&format!("mod [{}] fire", name), stage_begin, Some(ready_at - stage_begin)));
+                self.events.emit_mod(&name, RingsEventKind::Fired);
+            }
+            self.moments.push(Moment::since(&format!("fire stage {}", index), stage_begin));
+        }
+
+        self.moments.push(Moment::since(MOMENT_FIRE, begin));
+        self.transit(RingState::Working);
+
+        Ok(())
+    }
+
+    /// group mods into fire stages, each stage holds indexes of `self.mods`.
+    /// a mod is placed after every mod it depends on and never before a mod with a lower level,
+    /// a mod depending on a mod of a higher level is fired with that level.
+    fn fire_stages(&self) -> ResultBoxedE<Vec<Vec<usize>>> {
+        let names: Vec<String> = self.mods.iter().map(|m| m.name()).collect();
+
+        let mut depends: Vec<Vec<usize>> = Vec::with_capacity(self.mods.len());
+        for (i, m) in self.mods.iter().enumerate() {
+            let mut found = vec![];
+            for dependency in m.dependencies() {
+                let matched: Vec<usize> = names.iter().enumerate().filter(|(_, n)| n.eq(&&dependency)).map(|(j, _)| j).collect();
+                if matched.is_empty() && self.restarting(&dependency) {
+                    continue;
+                }
+                if matched.is_empty() {
+                    return Err(Erx::boxed(&format!("mod:[ {} ] depends on mod:[ {} ] which is not registered", names[i], dependency)));
+                }
+                found.extend(matched);
+            }
+            depends.push(found);
+        }
+
+        // Kahn's algorithm, mods left unordered are part of a dependency cycle
+        let mut pending: Vec<usize> = depends.iter().map(|d| d.len()).collect();
+        let mut queue: VecDeque<usize> = (0..self.mods.len()).filter(|i| pending[*i] == 0).collect();
+        let mut ordered: Vec<usize> = Vec::with_capacity(self.mods.len());
+        while let Some(i) = queue.pop_front() {
+            ordered.push(i);
+            for (j, d) in depends.iter().enumerate() {
+                for _ in d.iter().filter(|x| **x == i) {
+                    pending[j] -= 1;
+                    if pending[j] == 0 {
+                        queue.push_back(j);
+                    }
+                }
+            }
+        }
+
+        if ordered.len() < self.mods.len() {
+            let cycle: Vec<&str> = (0..self.mods.len()).filter(|i| !ordered.contains(i)).map(|i| names[i].as_str()).collect();
+            return Err(Erx::boxed(&format!("mods dependency cycle detected among: [ {} ]", cycle.join(", "))));
+        }
+
+        // effective level, raised to the levels of the dependencies
+        let mut levels: Vec<i64> = self.mods.iter().map(|m| m.level()).collect();
+        for i in ordered.iter().copied() {
+            if let Some(level) = depends[i].iter().map(|d| levels[*d]).max() {
+                levels[i] = levels[i].max(level);
+            }
+        }
+        // still topological, dependencies never have a higher effective level
+        ordered.sort_by_key(|i| levels[*i]);
+
+        // every level starts after the last stage of the lower levels
+        let mut ranks: Vec<usize> = vec![0; self.mods.len()];
+        let (mut level, mut floor, mut top) = (None, 0usize, 0usize);
+        for i in ordered {
+            if level != Some(levels[i]) {
+                floor = if level.is_some() { top + 1 } else { 0 };
+                level = Some(levels[i]);
+            }
+            ranks[i] = depends[i].iter().map(|d| ranks[*d] + 1).fold(floor, usize::max);
+            top = top.max(ranks[i]);
+        }
+
+        let mut stages: Vec<Vec<usize>> = vec![vec![]; ranks.iter().max().map_or(0, |r| r + 1)];
+        for (i, rank) in ranks.iter().enumerate() {
+            stages[*rank].push(i);
+        }
+        stages.retain(|s| !s.is_empty());
+
+        Ok(stages)
+    }
+
+    /// time a mod may take to shut down: its configured or own deadline, within the whole shutdown deadline
+    fn mod_shutdown_timeout(conf: &crate::conf::Shutdown, md: &dyn RingsMod, deadline: tokio::time::Instant) -> std::time::Duration {
+        let timeout = conf.mod_deadline(&md.name()).or(md.shutdown_deadline()).unwrap_or(MOD_TERMINATE_TIMEOUT);
+        timeout.min(deadline.saturating_duration_since(tokio::time::Instant::now()))
     }
 
+    /// wait until mod is ready or working
+    async fn wait_mod_ready(md: &dyn RingsMod) -> ResultBoxedEX {
+        let ready = |s: &RingState| !matches!(s, RingState::Init | RingState::Unknown);
+        let reached = match Self::wait_mod_stage(md, MOD_READY_TIMEOUT, ready).await {
+            Some(reached) => reached,
+            None => return Err(Erx::boxed(&format!("mod:[ {} ] not ready after {:?}", md.name(), MOD_READY_TIMEOUT))),
+        };
+
+        match reached {
+            RingState::Terminating | RingState::Terminated => Err(Erx::boxed(&format!("mod:[ {} ] terminated before ready", md.name()))),
+            RingState::Failed => Err(Erx::boxed(&format!("mod:[ {} ] failed before ready", md.name()))),
+            _ => Ok(()),
+        }
+    }
+
+    /// wait until mod is terminated, return false on timeout
+    async fn wait_mod_terminated(md: &dyn RingsMod, timeout: std::time::Duration) -> bool {
+        Self::wait_mod_stage(md, timeout, |s| matches!(s, RingState::Terminated | RingState::Failed)).await.is_some()
+    }
+
+    /// wait until mod stage satisfies `pred`, return None on timeout.
+    /// mods providing `stage_watch` are awaited on changes, others are polled.
+    async fn wait_mod_stage(md: &dyn RingsMod, timeout: std::time::Duration, pred: impl Fn(&RingState) -> bool) -> Option<RingState> {
+        let waiting = async {
+            if let Some(mut rx) = md.stage_watch() {
+                let matched = rx.wait_for(&pred).await.map(|s| *s);
+                if let Ok(matched) = matched {
+                    return matched;
+                }
+            }
+
+            loop {
+                let current = md.stage().await;
+                if pred(&current) {
+                    return current;
+                }
+                tokio::time::sleep(MOD_STAGE_POLL).await;
+            }
+        };
+
+        tokio::time::timeout(timeout, waiting).await.ok()
+    }
+
+    /// stages of all mods, mods given up by supervision are `Failed`
     pub async fn mods_stages(&self) -> HashMap<String, RingState> {
         let mut map: HashMap<String, RingState> = HashMap::new();
         for m in self.mods.iter() {
-            map.insert(m.name().to_string(), m.stage().await);
+            let given_up = self.supervisions.get(&m.name()).is_some_and(|s| s.given_up);
+            let stage = if given_up { RingState::Failed } else { m.stage().await };
+            map.insert(m.name().to_string(), stage);
+        }
+        for (name, _) in self.supervisions.iter().filter(|(_, s)| s.restarting && !s.removed) {
+            map.insert(name.clone(), RingState::Init);
         }
         map
     }
 
+    /// mod taken out by supervision to restart
+    fn restarting(&self, name: &str) -> bool {
+        self.supervisions.get(name).is_some_and(|s| s.restarting)
+    }
+
+    /// one supervision round, schedule restarts of failed mods following their restart policy.
+    /// mods due to restart are taken out of the application, to be restarted by `restart_mod`
+    /// without the application lock held and handed back by `restarted`.
+    /// returns the mods taken out, and when the next scheduled restart is due.
+    async fn supervise_once(&mut self) -> (Vec<Box<dyn RingsMod>>, Option<tokio::time::Instant>) {
+        if self.state.get() != RingState::Working {
+            return (vec![], None);
+        }
+
+        let now = tokio::time::Instant::now();
+        let mut next: Option<tokio::time::Instant> = None;
+        let mut due: Vec<String> = vec![];
+        for md in self.mods.iter() {
+            let name = md.name();
+            let supervision = self.supervisions.entry(name.clone()).or_default();
+            if supervision.given_up || !(supervision.fire_failed || md.stage().await == RingState::Failed) {
+                continue;
+            }
+
+            let restart_at = match supervision.restart_at {
+                Some(restart_at) => restart_at,
+                None => match md.restart_policy().backoff(supervision.attempts) {
+                    None => {
+                        supervision.given_up = true;
+                        error!("rings mod:[ {} ] failed, give up after {} restarts", name, supervision.attempts);
+                        let error = format!("give up after {} restarts", supervision.attempts);
+                        self.events.emit_mod(&name, RingsEventKind::Failed { error });
+                        continue;
+                    },
+                    Some(delay) => {
+                        warn!("rings mod:[ {} ] failed, restart in {:?}", name, delay);
+                        let error = format!("restart in {:?}", delay);
+                        self.events.emit_mod(&name, RingsEventKind::Failed { error });
+                        supervision.restart_at = Some(now + delay);
+                        now + delay
+                    },
+                },
+            };
+
+            if restart_at > now {
+                next = Some(next.map_or(restart_at, |n| n.min(restart_at)));
+                continue;
+            }
+
+            supervision.restart_at = None;
+            supervision.attempts += 1;
+            supervision.restarting = true;
+            info!("rings mod:[ {} ] restarting, attempt {}", name, supervision.attempts);
+            due.push(name);
+        }
+
+        let mut taken: Vec<Box<dyn RingsMod>> = Vec::with_capacity(due.len());
+        let mut i = 0;
+        while i < self.mods.len() {
+            if due.contains(&self.mods[i].name()) {
+                taken.push(self.mods.remove(i));
+            } else {
+                i += 1;
+            }
+        }
+
+        (taken, next)
+    }
+
+    /// restart a mod taken out by `supervise_once`: force stop, fire, and wait it ready
+    async fn restart_mod(md: &mut dyn RingsMod, events: &RingsEvents) -> ResultBoxedEX {
+        if let Err(ex) = md.force_stop().await {
+            warn!("rings mod:[ {} ] force stop before restart: {}", md.name(), ex.message());
+        }
+
+        events.firing(md.fire()).await?;
+        Self::wait_mod_ready(md).await
+    }
+
+    /// hand a mod restarted by `restart_mod` back and record the outcome.
+    /// a mod removed meanwhile is stopped and dropped, a mod restarted while the application
+    /// stopped working is paused or stopped along with it.
+    async fn restarted(&mut self, mut md: Box<dyn RingsMod>, fired: ResultBoxedEX) {
+        let name = md.name();
+        let supervision = self.supervisions.entry(name.clone()).or_default();
+        supervision.restarting = false;
+
+        if supervision.removed {
+            self.supervisions.remove(&name);
+            if let Err(ex) = md.force_stop().await {
+                error!("failed to force stop: {} error: {}", name, ex.message());
+            }
+            self.events.emit_mod(&name, RingsEventKind::Terminated);
+            return;
+        }
+
+        match fired {
+            Ok(_) => {
+                supervision.fire_failed = false;
+                info!("rings mod:[ {} ] restarted", name);
+                self.events.emit_mod(&name, RingsEventKind::Fired);
+            },
+            Err(ex) => {
+                supervision.fire_failed = true;
+                error!("rings mod:[ {} ] restart failed: {}", name, ex.message());
+            },
+        }
+
+        match self.state.get() {
+            RingState::Working => {},
+            RingState::Paused => {
+                if let Err(ex) = md.pause().await {
+                    warn!("rings mod:[ {} ] not paused: {}", name, ex.message());
+                }
+            },
+            _ => {
+                if let Err(ex) = md.force_stop().await {
+                    error!("failed to force stop: {} error: {}", name, ex.message());
+                }
+            },
+        }
+
+        self.mods.push(md);
+        self.mods.sort_by_key(|m| m.level());
+    }
+
+    /// supervise mods until the application terminating, restarts run without the application lock held.
+    /// woken on any mod or application stage change and when a restart is due,
+    /// only mods without `stage_watch` are polled, every `SUPERVISE_INTERVAL`.
+    async fn supervise(app: RingsApplication) {
+        let state = app.read().await.state();
+        loop {
+            // watch before looking at the stages, a change in between wakes the next round
+            let mut app_state = state.subscribe();
+            let (mut watches, polled, due, next, events) = {
+                let mut app = app.write().await;
+                let watches: Vec<watch::Receiver<RingState>> = app.mods.iter().filter_map(|m| m.stage_watch()).collect();
+                let polled = watches.len() < app.mods.len();
+                let (due, next) = app.supervise_once().await;
+                (watches, polled, due, next, app.events.clone())
+            };
+
+            if !due.is_empty() {
+                let events = &events;
+                let restarts = due.into_iter().map(|mut md| async move {
+                    let fired = Self::restart_mod(md.as_mut(), events).await;
+                    (md, fired)
+                });
+                let restarted = futures_util::future::join_all(restarts).await;
+
+                let mut app = app.write().await;
+                for (md, fired) in restarted {
+                    app.restarted(md, fired).await;
+                }
+                continue;
+            }
+
+            let changed = async {
+                let changes = watches.iter_mut().chain(std::iter::once(&mut app_state)).map(|w| {
+                    Box::pin(async move {
+                        if w.changed().await.is_err() {
+                            std::future::pending::<()>().await;
+                        }
+                    })
+                });
+                futures_util::future::select_all(changes).await;
+            };
+
+            let poll = polled.then(|| tokio::time::Instant::now() + SUPERVISE_INTERVAL);
+            let wake = match (next, poll) {
+                (Some(next), Some(poll)) => Some(next.min(poll)),
+                (next, poll) => next.or(poll),
+            };
+            let due = async {
+                match wake {
+                    Some(wake) => tokio::time::sleep_until(wake).await,
+                    None => std::future::pending().await,
+                }
+            };
+
+            tokio::select! {
+                _ = state.wait_terminating() => break,
+                _ = changed => {},
+                _ = due => {},
+            }
+        }
+    }
+
     pub async fn mods_all_terminated(&self) -> bool {
         for m in self.mods.iter() {
             if m.stage().await != RingState::Terminated {
@@ -454,69 +1247,60 @@ impl Rings {
     }
 
     pub async fn set_state(&self, state: RingState) {
-        *self.state.write().await = state;
+        self.transit(state);
     }
 
     async fn serve(app: &RingsApplication) {
-        let _ = tokio::join!(Self::catch_signal(app), Self::holding(app));
+        tokio::spawn(Self::supervise(Arc::clone(app)));
+
+        tokio::select! {
+            _ = Self::catch_signal(app) => {},
+            _ = Self::holding(app) => {},
+        }
     }
 
+    /// SIGTERM, SIGINT and SIGQUIT shutdown the application, SIGHUP invokes reload hooks
+    #[cfg(unix)]
     async fn catch_signal(app: &RingsApplication) {
+        use tokio::signal::unix::{signal, SignalKind};
+
         info!("Catch signal started");
         let app = app.clone();
-        tokio::signal::ctrl_c().await.expect("attempt to terminate immediately");
-        info!("rings::listen_signal_kill received Ctrl-C, shutting down");
-
-        // match app.write().await {
-        //     Ok(mut write_app) => {
-        //         write_app.shutdown().await;
-        //     },
-        //     Err(er) => {
-        //         error!("failed to listen_signal_kill: {}", er);
-        //     },
-        // };
 
-        let mut write_app = app.write().await;
-        write_app.shutdown().await;
-    }
-
-    async fn holding(app: &RingsApplication) {
-        let app = Arc::clone(app);
-        const MAX_CONSECUTIVE_FAILURES: i32 = 8;
-        let duration = tokio::time::Duration::from_millis(100);
-        let mut consecutive_failures = 0;
-
-        loop {
-            if consecutive_failures > MAX_CONSECUTIVE_FAILURES {
-                error!("Rings::wait lock error count: {}, now break wait", consecutive_failures);
-                break;
+        let listen = |kind: SignalKind| signal(kind).expect("unable to listen signal");
+        let (mut term, mut int, mut quit, mut hup) =
+            (listen(SignalKind::terminate()), listen(SignalKind::interrupt()), listen(SignalKind::quit()), listen(SignalKind::hangup()));
+
+        let received = loop {
+            tokio::select! {
+                _ = term.recv() => break "SIGTERM",
+                _ = int.recv() => break "SIGINT",
+                _ = quit.recv() => break "SIGQUIT",
+                _ = hup.recv() => {
+                    info!("rings::catch_signal received SIGHUP, reloading");
+                    app.read().await.reload().await;
+                },
             }
+        };
 
-            tokio::time::sleep(duration).await;
-
-            match app.try_read() {
-                Ok(ring) => {
-                    consecutive_failures = 0;
-                    let stat = ring.get_state_unchecked();
-                    if stat != RingState::Terminating && stat != RingState::Terminated {
-                        continue;
-                    }
+        info!("rings::catch_signal received {}, shutting down", received);
+        app.write().await.shutdown().await;
+    }
 
-                    if ring.mods_all_terminated().await {
-                        info!("all mods terminated, breaking loop");
-                        break;
-                    }
+    #[cfg(not(unix))]
+    async fn catch_signal(app: &RingsApplication) {
+        info!("Catch signal started");
+        let app = app.clone();
+        tokio::signal::ctrl_c().await.expect("attempt to terminate immediately");
+        info!("rings::catch_signal received Ctrl-C, shutting down");
 
-                    let mod_stages = ring.mods_stages().await;
-                    info!("mod stages: {:?}", mod_stages);
-                },
-                Err(_) => {
-                    consecutive_failures += 1;
-                },
-            }
-        }
+        app.write().await.shutdown().await;
+    }
 
-        app.write().await.set_state(RingState::Terminated).await;
+    async fn holding(app: &RingsApplication) {
+        let state = app.read().await.state();
+        state.wait_for(|s| *s == RingState::Terminated).await;
+        info!("rings terminated, stop holding, mod stages: {:?}", app.read().await.mods_stages().await);
     }
 
     pub fn description(&self) -> String {
@@ -533,10 +1317,508 @@ impl Rings {
 
 #[cfg(test)]
 mod tests {
+    use super::*;
+
+    struct StageMod {
+        name: String,
+        level: i64,
+        dependencies: Vec<String>,
+        stage: SafeRingState,
+        hang_shutdown: bool,
+    }
+
+    fn stage_mod(name: &str, level: i64, dependencies: &[&str]) -> Box<dyn RingsMod> {
+        Box::new(StageMod {
+            name: name.to_string(),
+            level,
+            dependencies: dependencies.iter().map(|d| d.to_string()).collect(),
+            stage: RingState::inited_safe_ring_state(),
+            hang_shutdown: false,
+        })
+    }
+
+    /// never returns from shutdown
+    /// terminates a while after shutdown, like a mod draining its task
+    struct DrainingMod {
+        stage: SafeRingState,
+        shutdown_ran: Arc<std::sync::atomic::AtomicBool>,
+        force_stopped: Arc<std::sync::atomic::AtomicBool>,
+    }
+
+    #[async_trait]
+    impl RingsMod for DrainingMod {
+        fn name(&self) -> String {
+            "draining".to_string()
+        }
+
+        fn duplicate_able(&self) -> bool {
+            false
+        }
+
+        async fn initialize(&mut self) -> ResultBoxedEX {
+            RingState::safe_ring_state_must_set(&self.stage, RingState::Ready).await
+        }
+
+        async fn unregister(&mut self) -> ResultBoxedEX {
+            self.shutdown().await
+        }
+
+        async fn shutdown(&mut self) -> ResultBoxedEX {
+            self.shutdown_ran.store(true, std::sync::atomic::Ordering::SeqCst);
+            self.stage.set(RingState::Terminating);
+            let stage = self.stage.clone();
+            tokio::spawn(async move {
+                tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+                stage.set(RingState::Terminated);
+            });
+            Ok(())
+        }
+
+        async fn force_stop(&mut self) -> ResultBoxedEX {
+            self.force_stopped.store(true, std::sync::atomic::Ordering::SeqCst);
+            RingState::safe_ring_state_must_set(&self.stage, RingState::Terminated).await
+        }
+
+        async fn fire(&mut self) -> ResultBoxedEX {
+            RingState::safe_ring_state_must_set(&self.stage, RingState::Working).await
+        }
+
+        async fn stage(&self) -> RingState {
+            self.stage.get()
+        }
+
+        fn level(&self) -> i64 {
+            0
+        }
+
+        fn stage_watch(&self) -> Option<watch::Receiver<RingState>> {
+            Some(self.stage.subscribe())
+        }
+    }
+
+    crate::impl_any_trait!(DrainingMod);
+
+    fn hanging_mod(name: &str) -> Box<dyn RingsMod> {
+        Box::new(StageMod { name: name.to_string(), level: 0, dependencies: vec![], stage: RingState::inited_safe_ring_state(), hang_shutdown: true })
+    }
+
+    #[async_trait]
+    impl RingsMod for StageMod {
+        fn name(&self) -> String {
+            self.name.clone()
+        }
+
+        fn duplicate_able(&self) -> bool {
+            false
+        }
+
+        async fn initialize(&mut self) -> ResultBoxedEX {
+            RingState::safe_ring_state_must_set(&self.stage, RingState::Ready).await
+        }
+
+        async fn unregister(&mut self) -> ResultBoxedEX {
+            self.shutdown().await
+        }
+
+        async fn shutdown(&mut self) -> ResultBoxedEX {
+            if self.hang_shutdown {
+                std::future::pending::<()>().await;
+            }
+            RingState::safe_ring_state_must_set(&self.stage, RingState::Terminated).await
+        }
+
+        fn shutdown_deadline(&self) -> Option<std::time::Duration> {
+            Some(std::time::Duration::from_millis(100))
+        }
+
+        async fn fire(&mut self) -> ResultBoxedEX {
+            RingState::safe_ring_state_must_set(&self.stage, RingState::Working).await
+        }
+
+        async fn stage(&self) -> RingState {
+            self.stage.get()
+        }
+
+        fn level(&self) -> i64 {
+            self.level
+        }
+
+        fn dependencies(&self) -> Vec<String> {
+            self.dependencies.clone()
+        }
+
+        fn stage_watch(&self) -> Option<watch::Receiver<RingState>> {
+            Some(self.stage.subscribe())
+        }
+
+        async fn pause(&mut self) -> ResultBoxedEX {
+            RingState::safe_ring_state_must_set(&self.stage, RingState::Paused).await
+        }
+
+        async fn resume(&mut self) -> ResultBoxedEX {
+            RingState::safe_ring_state_must_set(&self.stage, RingState::Working).await
+        }
+    }
+
+    crate::impl_any_trait!(StageMod);
+
+    /// fails its background task `failures` times after fired, then works
+    struct FlakyMod {
+        failures: Arc<std::sync::atomic::AtomicU32>,
+        policy: RestartPolicy,
+        stage: SafeRingState,
+    }
+
+    fn flaky_mod(failures: u32, policy: RestartPolicy) -> Box<dyn RingsMod> {
+        Box::new(FlakyMod { failures: Arc::new(failures.into()), policy, stage: RingState::inited_safe_ring_state() })
+    }
+
+    #[async_trait]
+    impl RingsMod for FlakyMod {
+        fn name(&self) -> String {
+            "flaky".to_string()
+        }
+
+        fn duplicate_able(&self) -> bool {
+            false
+        }
+
+        async fn initialize(&mut self) -> ResultBoxedEX {
+            Ok(())
+        }
+
+        async fn unregister(&mut self) -> ResultBoxedEX {
+            self.shutdown().await
+        }
+
+        async fn shutdown(&mut self) -> ResultBoxedEX {
+            RingState::safe_ring_state_must_set(&self.stage, RingState::Terminating).await
+        }
+
+        async fn fire(&mut self) -> ResultBoxedEX {
+            self.stage.set(RingState::Working);
+            let (failures, stage) = (self.failures.clone(), self.stage.clone());
+            supervise::spawn_supervised("flaky", &self.stage, async move {
+                tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+                let left = failures.load(std::sync::atomic::Ordering::SeqCst);
+                if left > 0 {
+                    failures.store(left - 1, std::sync::atomic::Ordering::SeqCst);
+                    return Err(Erx::boxed("flaky failure"));
+                }
+                stage.wait_terminating().await;
+                Ok(())
+            });
+            Ok(())
+        }
+
+        async fn stage(&self) -> RingState {
+            self.stage.get()
+        }
+
+        fn level(&self) -> i64 {
+            0
+        }
+
+        fn stage_watch(&self) -> Option<watch::Receiver<RingState>> {
+            Some(self.stage.subscribe())
+        }
+
+        fn restart_policy(&self) -> RestartPolicy {
+            self.policy.clone()
+        }
+    }
+
+    crate::impl_any_trait!(FlakyMod);
+
+    fn rings_with(mods: Vec<Box<dyn RingsMod>>) -> Rings {
+        Rings {
+            name: "test_rings".to_string(),
+            mods,
+            state: RingState::inited_safe_ring_state(),
+            moments: vec![],
+            services: ServiceManager::make("test_rings"),
+            models: crate::model::connections("test_rings"),
+            reload_hooks: vec![],
+            events: RingsEvents::new("test_rings"),
+            supervisions: HashMap::new(),
+        }
+    }
+
+    fn stage_names(rings: &Rings) -> Vec<Vec<String>> {
+        let stages = rings.fire_stages().unwrap();
+        stages.iter().map(|s| s.iter().map(|i| rings.mods[*i].name()).collect()).collect()
+    }
 
     #[tokio::test]
-    async fn test_rings() {
+    async fn test_fire_stages() {
+        let rings = rings_with(vec![
+            stage_mod("web", 0, &["cache"]),
+            stage_mod("cache", 0, &[]),
+            stage_mod("db", 0, &[]),
+            stage_mod("scheduler", 10, &[]),
+        ]);
+
+        assert_eq!(stage_names(&rings), vec![vec!["cache", "db"], vec!["web"], vec!["scheduler"]]);
+
+        let rings = rings_with(vec![
+            stage_mod("web", 0, &["cron"]),
+            stage_mod("cache", 0, &[]),
+            stage_mod("cron", 10, &[]),
+            stage_mod("mailer", 5, &[]),
+        ]);
+
+        assert_eq!(stage_names(&rings), vec![vec!["cache"], vec!["mailer"], vec!["cron"], vec!["web"]]);
+    }
+
+    #[test]
+    fn test_fire_stages_errors() {
+        let missing = rings_with(vec![stage_mod("web", 0, &["cache"])]);
+        assert!(missing.fire_stages().unwrap_err().message().contains("not registered"));
 
-        // crate::rings::add_rings_invoke_macro("test_mod")
+        let cycle = rings_with(vec![stage_mod("a", 0, &["b"]), stage_mod("b", 0, &["a"]), stage_mod("c", 0, &[])]);
+        let message = cycle.fire_stages().unwrap_err().message_string();
+        assert!(message.contains("cycle detected among: [ a, b ]"));
+    }
+
+    #[tokio::test]
+    async fn test_fire_and_shutdown() {
+        let mut rings = rings_with(vec![stage_mod("web", 0, &["cache"]), stage_mod("cache", 0, &[])]);
+        rings.fire().await.unwrap();
+        assert_eq!(rings.get_state().unwrap(), RingState::Working);
+
+        let state = rings.state();
+        let waiting = tokio::spawn(async move { state.wait_for(|s| *s == RingState::Terminated).await });
+
+        rings.shutdown().await;
+        assert!(rings.mods_all_terminated().await);
+        assert_eq!(waiting.await.unwrap(), RingState::Terminated);
+    }
+
+    #[tokio::test]
+    async fn test_shutdown_hanging_mod() {
+        let mut rings = rings_with(vec![hanging_mod("web"), stage_mod("cache", 0, &[])]);
+        rings.fire().await.unwrap();
+
+        let shutdown = tokio::time::timeout(std::time::Duration::from_secs(5), rings.shutdown()).await;
+        assert!(shutdown.is_ok());
+        assert_eq!(rings.mods[1].stage().await, RingState::Terminated);
+    }
+
+    #[tokio::test]
+    async fn test_fire_timeline() {
+        let mut rings = rings_with(vec![stage_mod("web", 0, &["cache"]), stage_mod("cache", 0, &[])]);
+        rings.add_moment(Moment::now(MOMENT_MAKE));
+        rings.fire().await.unwrap();
+
+        let timeline = rings.timeline();
+        let names: Vec<&str> = timeline.steps.iter().map(|s| s.name.as_str()).collect();
+        for name in ["mod [cache] fire", "mod [web] fire", "fire stage 0", "fire stage 1", "fire"] {
+            assert!(names.contains(&name), "missing step {}", name);
+        }
+        assert!(timeline.startup.is_some());
+        assert!(timeline.steps.iter().all(|s| s.offset >= 0));
+    }
+
+    #[tokio::test]
+    async fn test_lifecycle_events() {
+        let mut rings = rings_with(vec![stage_mod("web", 0, &["cache"]), stage_mod("cache", 0, &[])]);
+        let mut events = rings.subscribe();
+
+        rings.fire().await.unwrap();
+        rings.shutdown().await;
+
+        let mut received = vec![];
+        while let Ok(event) = events.try_recv() {
+            received.push((event.mod_name.unwrap_or_default(), event.kind));
+        }
+
+        let position = |name: &str, kind: RingsEventKind| received.iter().position(|e| e.0 == name && e.1 == kind).unwrap();
+        assert!(position("cache", RingsEventKind::Fired) < position("web", RingsEventKind::Fired));
+        assert!(position("web", RingsEventKind::Fired) < position("", RingsEventKind::StateChanged { from: RingState::Init, to: RingState::Working }));
+        assert!(position("", RingsEventKind::ShutdownRequested) < position("web", RingsEventKind::Terminated));
+        assert!(position("web", RingsEventKind::Terminated) < position("cache", RingsEventKind::Terminated));
+        assert_eq!(received.last(), Some(&("".to_string(), RingsEventKind::Terminated)));
+    }
+
+    #[tokio::test]
+    async fn test_mod_stage_events() {
+        let mut rings = rings_with(vec![]);
+        let mut events = rings.subscribe();
+        rings.register_mod(StageMod { name: "cache".to_string(), level: 0, dependencies: vec![], stage: RingState::inited_safe_ring_state(), hang_shutdown: false }).await;
+        rings.register_mod(FlakyMod { failures: Arc::new(1.into()), policy: RestartPolicy::Never, stage: RingState::inited_safe_ring_state() }).await;
+        rings.fire().await.unwrap();
+
+        let mut received = vec![];
+        let failed = tokio::time::timeout(std::time::Duration::from_secs(1), async {
+            let (mut failed, mut changed) = (false, false);
+            while let Ok(event) = events.recv().await {
+                failed |= matches!(&event.kind, RingsEventKind::Failed { error } if error.contains("flaky failure"));
+                changed |= matches!(&event.kind, RingsEventKind::StateChanged { to: RingState::Failed, .. });
+                received.push((event.mod_name.unwrap_or_default(), event.kind));
+                if failed && changed {
+                    return;
+                }
+            }
+        })
+        .await;
+        assert!(failed.is_ok(), "no failure event: {:?}", received);
+
+        let position = |name: &str, kind: RingsEventKind| received.iter().position(|e| e.0 == name && e.1 == kind);
+        assert!(position("cache", RingsEventKind::Registered) < position("cache", RingsEventKind::Initialized));
+        assert!(position("cache", RingsEventKind::StateChanged { from: RingState::Ready, to: RingState::Working }).is_some());
+        assert!(position("flaky", RingsEventKind::StateChanged { from: RingState::Working, to: RingState::Failed }).is_some());
+    }
+
+    async fn supervise_until(rings: &mut Rings, pred: impl Fn(&Supervision, RingState) -> bool) {
+        for _ in 0..200 {
+            let (due, _) = rings.supervise_once().await;
+            for mut md in due {
+                let fired = Rings::restart_mod(md.as_mut(), &rings.events.clone()).await;
+                rings.restarted(md, fired).await;
+            }
+            let supervision = rings.supervisions.get("flaky").cloned().unwrap_or_default();
+            if pred(&supervision, rings.mods[0].stage().await) {
+                return;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(5)).await;
+        }
+        panic!("supervision not settled");
+    }
+
+    #[tokio::test]
+    async fn test_supervise_restart() {
+        let policy = RestartPolicy::OnFailure {
+            initial: std::time::Duration::from_millis(1),
+            max: std::time::Duration::from_millis(4),
+            max_retries: 3,
+        };
+        let mut rings = rings_with(vec![flaky_mod(2, policy)]);
+        rings.fire().await.unwrap();
+
+        supervise_until(&mut rings, |s, stage| s.attempts == 2 && stage == RingState::Working).await;
+        tokio::time::sleep(std::time::Duration::from_millis(30)).await;
+        assert_eq!(rings.mods_stages().await.get("flaky"), Some(&RingState::Working));
+
+        rings.shutdown().await;
+        assert!(rings.mods_all_terminated().await);
+    }
+
+    #[tokio::test]
+    async fn test_supervise_give_up() {
+        let policy = RestartPolicy::OnFailure {
+            initial: std::time::Duration::from_millis(1),
+            max: std::time::Duration::from_millis(1),
+            max_retries: 2,
+        };
+        let mut rings = rings_with(vec![flaky_mod(u32::MAX, policy)]);
+        let mut events = rings.subscribe();
+        rings.fire().await.unwrap();
+
+        supervise_until(&mut rings, |s, _| s.given_up).await;
+        assert_eq!(rings.supervisions["flaky"].attempts, 2);
+        assert_eq!(rings.mods_stages().await.get("flaky"), Some(&RingState::Failed));
+
+        let mut gave_up = false;
+        while let Ok(event) = events.try_recv() {
+            gave_up |= matches!(event.kind, RingsEventKind::Failed { ref error } if error.contains("give up"));
+        }
+        assert!(gave_up);
+
+        let mut never = rings_with(vec![flaky_mod(1, RestartPolicy::Never)]);
+        never.fire().await.unwrap();
+        supervise_until(&mut never, |s, _| s.given_up).await;
+        assert_eq!(never.supervisions["flaky"].attempts, 0);
+    }
+
+    #[tokio::test]
+    async fn test_supervise_remove_while_restarting() {
+        let policy = RestartPolicy::OnFailure {
+            initial: std::time::Duration::from_millis(1),
+            max: std::time::Duration::from_millis(1),
+            max_retries: 3,
+        };
+        let mut rings = rings_with(vec![flaky_mod(1, policy)]);
+        rings.fire().await.unwrap();
+
+        let mut due = vec![];
+        for _ in 0..200 {
+            (due, _) = rings.supervise_once().await;
+            if !due.is_empty() {
+                break;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(5)).await;
+        }
+        assert_eq!(due.len(), 1);
+        assert!(rings.mods.is_empty());
+        assert_eq!(rings.mods_stages().await.get("flaky"), Some(&RingState::Init));
+
+        rings.remove_mod("flaky").await.unwrap();
+        let mut md = due.pop().unwrap();
+        let fired = Rings::restart_mod(md.as_mut(), &rings.events.clone()).await;
+        rings.restarted(md, fired).await;
+        assert!(rings.mods.is_empty());
+        assert!(rings.supervisions.is_empty());
+        assert!(rings.mods_stages().await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_plug_and_remove_mod() {
+        let mut rings = rings_with(vec![stage_mod("cache", 0, &[])]);
+        rings.plug_mod(StageMod { name: "db".to_string(), level: 0, dependencies: vec![], stage: RingState::inited_safe_ring_state(), hang_shutdown: false }).await.unwrap();
+        assert_eq!(rings.mods_stages().await.get("db"), Some(&RingState::Ready));
+
+        rings.fire().await.unwrap();
+        let mut events = rings.subscribe();
+
+        let web = StageMod { name: "web".to_string(), level: 0, dependencies: vec!["cache".to_string()], stage: RingState::inited_safe_ring_state(), hang_shutdown: false };
+        rings.plug_mod(web).await.unwrap();
+        assert_eq!(rings.mods_stages().await.get("web"), Some(&RingState::Working));
+        assert!(matches!(events.try_recv().unwrap().kind, RingsEventKind::Registered));
+        assert!(matches!(events.try_recv().unwrap().kind, RingsEventKind::Initialized));
+
+        let orphan = StageMod { name: "orphan".to_string(), level: 0, dependencies: vec!["nope".to_string()], stage: RingState::inited_safe_ring_state(), hang_shutdown: false };
+        assert!(rings.plug_mod(orphan).await.is_err());
+        assert!(!rings.mods_stages().await.contains_key("orphan"));
+
+        let message = rings.remove_mod("cache").await.unwrap_err().message_string();
+        assert!(message.contains("depended on by: [ web ]"));
+
+        let removing = rings.get_mod::<StageMod>("web").unwrap().stage.clone();
+        rings.remove_mod("web").await.unwrap();
+        assert_eq!(removing.get(), RingState::Terminated);
+        assert!(!rings.mods_stages().await.contains_key("web"));
+        assert!(rings.remove_mod("web").await.is_err());
+
+        rings.remove_mod("cache").await.unwrap();
+        assert_eq!(rings.get_state().unwrap(), RingState::Working);
+    }
+
+    #[tokio::test]
+    async fn test_remove_ready_mod() {
+        let mut rings = rings_with(vec![]);
+        let draining = DrainingMod { stage: RingState::inited_safe_ring_state(), shutdown_ran: Default::default(), force_stopped: Default::default() };
+        let (stage, shutdown_ran, force_stopped) = (draining.stage.clone(), draining.shutdown_ran.clone(), draining.force_stopped.clone());
+        rings.plug_mod(draining).await.unwrap();
+        assert_eq!(stage.get(), RingState::Ready);
+
+        rings.remove_mod("draining").await.unwrap();
+        assert!(shutdown_ran.load(std::sync::atomic::Ordering::SeqCst));
+        assert!(!force_stopped.load(std::sync::atomic::Ordering::SeqCst));
+        assert_eq!(stage.get(), RingState::Terminated);
+    }
+
+    #[tokio::test]
+    async fn test_pause_and_resume() {
+        let mut rings = rings_with(vec![stage_mod("web", 0, &[])]);
+        assert!(rings.pause().await.is_err());
+
+        rings.fire().await.unwrap();
+        rings.pause().await.unwrap();
+        assert_eq!(rings.get_state().unwrap(), RingState::Paused);
+        assert_eq!(rings.mods_stages().await.get("web"), Some(&RingState::Paused));
+
+        rings.resume().await.unwrap();
+        assert_eq!(rings.get_state().unwrap(), RingState::Working);
+        assert_eq!(rings.mods_stages().await.get("web"), Some(&RingState::Working));
     }
 }
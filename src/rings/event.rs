@@ -0,0 +1,111 @@
+use crate::rings::RingState;
+use serde::{Deserialize, Serialize};
+use tokio::sync::{broadcast, watch};
+
+/// events kept for slow subscribers, older events are dropped and the receiver gets `Lagged`
+pub const RINGS_EVENT_CAPACITY: usize = 256;
+
+/// Rings lifecycle event
+///
+/// # Fields
+///
+/// * `app` - The rings application name.
+/// * `mod_name` - The mod the event belongs to, `None` for application level events.
+/// * `kind` - What happened.
+/// * `time` - Timestamp in micros.
+#[derive(Deserialize, Serialize, Clone, Debug)]
+pub struct RingsEvent {
+    pub app: String,
+    pub mod_name: Option<String>,
+    pub kind: RingsEventKind,
+    pub time: i64,
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq, Eq)]
+pub enum RingsEventKind {
+    /// mod registered to the application
+    Registered,
+    /// mod initialized
+    Initialized,
+    /// mod fired and ready
+    Fired,
+    /// state of application or mod changed
+    StateChanged { from: RingState, to: RingState },
+    /// mod failed to initialize, fire or terminate
+    Failed { error: String },
+    /// application shutdown requested
+    ShutdownRequested,
+    /// application or mod terminated
+    Terminated,
+}
+
+impl RingsEvent {
+    pub fn new(app: &str, mod_name: Option<&str>, kind: RingsEventKind) -> Self {
+        Self { app: app.to_string(), mod_name: mod_name.map(|m| m.to_string()), kind, time: chrono::Utc::now().timestamp_micros() }
+    }
+
+    /// event of the application itself
+    pub fn is_application(&self) -> bool {
+        self.mod_name.is_none()
+    }
+}
+
+tokio::task_local! {
+    /// events of the application firing a mod in the current task
+    static FIRING: RingsEvents;
+}
+
+/// Rings Events
+/// broadcasts lifecycle events to every subscriber, emitting without subscribers is a no-op.
+#[derive(Clone, Debug)]
+pub struct RingsEvents {
+    app: String,
+    sender: broadcast::Sender<RingsEvent>,
+}
+
+impl RingsEvents {
+    pub fn new(app: &str) -> Self {
+        let (sender, _) = broadcast::channel(RINGS_EVENT_CAPACITY);
+        Self { app: app.to_string(), sender }
+    }
+
+    /// subscribe events emitted from now on
+    pub fn subscribe(&self) -> broadcast::Receiver<RingsEvent> {
+        self.sender.subscribe()
+    }
+
+    /// emit an application level event
+    pub fn emit(&self, kind: RingsEventKind) {
+        let _ = self.sender.send(RingsEvent::new(&self.app, None, kind));
+    }
+
+    /// emit an event of mod
+    pub fn emit_mod(&self, mod_name: &str, kind: RingsEventKind) {
+        let _ = self.sender.send(RingsEvent::new(&self.app, Some(mod_name), kind));
+    }
+
+    /// emit `StateChanged` of mod on every change of its stage, until the stage is dropped
+    pub(crate) fn relay_mod_stages(&self, mod_name: &str, mut stage: watch::Receiver<RingState>) {
+        let (events, mod_name) = (self.clone(), mod_name.to_string());
+        let mut from = *stage.borrow_and_update();
+        tokio::spawn(async move {
+            while stage.changed().await.is_ok() {
+                let to = *stage.borrow_and_update();
+                if from != to {
+                    events.emit_mod(&mod_name, RingsEventKind::StateChanged { from, to });
+                    from = to;
+                }
+            }
+        });
+    }
+
+    /// run a mod fire with these events at hand, `supervise::spawn_supervised` reports task failures to them
+    pub(crate) async fn firing<F: std::future::Future>(&self, fire: F) -> F::Output {
+        FIRING.scope(self.clone(), fire).await
+    }
+
+    /// events of the application firing a mod in the current task
+    pub(crate) fn of_firing() -> Option<RingsEvents> {
+        FIRING.try_with(|events| events.clone()).ok()
+    }
+}
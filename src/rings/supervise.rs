@@ -0,0 +1,160 @@
+use crate::erx::ResultBoxedEX;
+use crate::rings::event::{RingsEventKind, RingsEvents};
+use crate::rings::{RingState, SafeRingState};
+use std::time::Duration;
+use tracing::{error, info};
+
+/// default first restart delay of `RestartPolicy::on_failure`
+pub const RESTART_INITIAL_BACKOFF: Duration = Duration::from_secs(1);
+
+/// default max restart delay of `RestartPolicy::on_failure`
+pub const RESTART_MAX_BACKOFF: Duration = Duration::from_secs(60);
+
+/// Restart Policy
+/// what rings does when a mod turns `Failed` after fired
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub enum RestartPolicy {
+    /// keep the mod failed
+    #[default]
+    Never,
+    /// restart the mod with exponential backoff, `initial` doubled on every attempt and capped by `max`,
+    /// give up after `max_retries` restarts during the application lifetime
+    OnFailure { initial: Duration, max: Duration, max_retries: u32 },
+}
+
+impl RestartPolicy {
+    /// restart on failure with default backoff
+    pub fn on_failure(max_retries: u32) -> Self {
+        RestartPolicy::OnFailure { initial: RESTART_INITIAL_BACKOFF, max: RESTART_MAX_BACKOFF, max_retries }
+    }
+
+    /// delay before restart attempt `attempt` (0 based), None when no more restart allowed
+    pub fn backoff(&self, attempt: u32) -> Option<Duration> {
+        match self {
+            RestartPolicy::Never => None,
+            RestartPolicy::OnFailure { initial, max, max_retries } => {
+                if attempt >= *max_retries {
+                    return None;
+                }
+                let factor = 2u32.checked_pow(attempt).unwrap_or(u32::MAX);
+                Some(initial.saturating_mul(factor).min(*max))
+            },
+        }
+    }
+}
+
+/// supervision record of one mod
+#[derive(Clone, Debug, Default)]
+pub(crate) struct Supervision {
+    /// restarts done
+    pub(crate) attempts: u32,
+    /// next restart scheduled at
+    pub(crate) restart_at: Option<tokio::time::Instant>,
+    /// last restart failed on fire, the mod stage may not tell it
+    pub(crate) fire_failed: bool,
+    /// no more restart, the mod stays failed
+    pub(crate) given_up: bool,
+    /// taken out of the application while restarting
+    pub(crate) restarting: bool,
+    /// removed while restarting, stopped once the restart returns
+    pub(crate) removed: bool,
+}
+
+/// spawn a mod background task under supervision
+/// the mod stage turns `Failed` when the task returns error, panics, or ends before the mod is terminating,
+/// and `Terminated` when it ends after terminating. aborting the returned handle leaves the stage untouched.
+/// spawned while the application fires the mod, a failure is emitted as a `Failed` event of the mod.
+///
+/// # Arguments
+///
+/// * `name` - The mod name, for logging and events.
+/// * `stage` - The mod stage.
+/// * `task` - The background task.
+///
+/// # Returns
+///
+/// * `AbortHandle` - abort the task, e.g. in `force_stop`.
+pub fn spawn_supervised<F>(name: &str, stage: &SafeRingState, task: F) -> tokio::task::AbortHandle
+where
+    F: std::future::Future<Output = ResultBoxedEX> + Send + 'static,
+{
+    let handle = tokio::spawn(task);
+    let abort = handle.abort_handle();
+
+    let (name, stage, events) = (name.to_string(), stage.clone(), RingsEvents::of_firing());
+    tokio::spawn(async move {
+        let failure = match handle.await {
+            Ok(Ok(_)) => None,
+            Ok(Err(ex)) => Some(ex.message_string()),
+            Err(ex) if ex.is_cancelled() => return,
+            Err(ex) => Some(format!("task panicked: {}", ex)),
+        };
+
+        let current = stage.get();
+        let terminating = matches!(current, RingState::Terminating | RingState::Terminated);
+        match failure {
+            None if terminating => {
+                stage.set(RingState::Terminated);
+            },
+            None => {
+                error!("mod:[ {} ] task exited while {:?}", name, current);
+                stage.set(RingState::Failed);
+                if let Some(events) = events {
+                    events.emit_mod(&name, RingsEventKind::Failed { error: format!("task exited while {:?}", current) });
+                }
+            },
+            Some(failure) if terminating => {
+                info!("mod:[ {} ] task exited while terminating: {}", name, failure);
+                stage.set(RingState::Terminated);
+            },
+            Some(failure) => {
+                error!("mod:[ {} ] task failed: {}", name, failure);
+                stage.set(RingState::Failed);
+                if let Some(events) = events {
+                    events.emit_mod(&name, RingsEventKind::Failed { error: failure });
+                }
+            },
+        }
+    });
+
+    abort
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_restart_backoff() {
+        assert_eq!(RestartPolicy::Never.backoff(0), None);
+
+        let policy = RestartPolicy::OnFailure { initial: Duration::from_secs(1), max: Duration::from_secs(5), max_retries: 4 };
+        assert_eq!(policy.backoff(0), Some(Duration::from_secs(1)));
+        assert_eq!(policy.backoff(1), Some(Duration::from_secs(2)));
+        assert_eq!(policy.backoff(2), Some(Duration::from_secs(4)));
+        assert_eq!(policy.backoff(3), Some(Duration::from_secs(5)));
+        assert_eq!(policy.backoff(4), None);
+    }
+
+    #[tokio::test]
+    async fn test_spawn_supervised() {
+        let stage = SafeRingState::new(RingState::Working);
+        spawn_supervised("failing", &stage, async { Err(crate::erx::Erx::boxed("boom")) });
+        assert_eq!(stage.wait_for(|s| *s == RingState::Failed).await, RingState::Failed);
+
+        let stage = SafeRingState::new(RingState::Working);
+        let watched = stage.clone();
+        spawn_supervised("graceful", &stage, async move {
+            watched.wait_terminating().await;
+            Ok(())
+        });
+        stage.set(RingState::Terminating);
+        assert_eq!(stage.wait_for(|s| *s == RingState::Terminated).await, RingState::Terminated);
+
+        let stage = SafeRingState::new(RingState::Working);
+        let abort = spawn_supervised("aborted", &stage, std::future::pending());
+        abort.abort();
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        assert_eq!(stage.get(), RingState::Working);
+    }
+}
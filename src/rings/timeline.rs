@@ -0,0 +1,116 @@
+use crate::rings::Moment;
+use serde::{Deserialize, Serialize};
+
+/// name of the moment recorded when a rings application made
+pub const MOMENT_MAKE: &str = "make";
+
+/// name of the moment spanning the whole fire of a rings application
+pub const MOMENT_FIRE: &str = "fire";
+
+/// Timeline
+/// lifecycle steps of a rings application, relative to its make moment
+///
+/// # Fields
+///
+/// * `app` - The rings application name.
+/// * `made` - Make time, micros since epoch.
+/// * `startup` - Micros from make to every mod fired, `None` before fired.
+/// * `steps` - Every recorded step, in time order.
+#[derive(Deserialize, Serialize, Clone, Debug)]
+pub struct Timeline {
+    pub app: String,
+    pub made: i64,
+    pub startup: Option<i64>,
+    pub steps: Vec<TimelineStep>,
+}
+
+/// Timeline Step
+///
+/// # Fields
+///
+/// * `name` - The moment name, e.g. `mod [web] fire`.
+/// * `offset` - Micros since made when the step began.
+/// * `elapsed` - Micros the step took, `None` for instant steps.
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq, Eq)]
+pub struct TimelineStep {
+    pub name: String,
+    pub offset: i64,
+    pub elapsed: Option<i64>,
+}
+
+fn millis(micros: i64) -> f64 {
+    micros as f64 / 1000.0
+}
+
+impl Timeline {
+    /// build timeline from moments, the first `make` moment is the origin
+    pub fn from_moments(app: &str, moments: &[Moment]) -> Self {
+        let made = moments.iter().find(|m| m.name() == MOMENT_MAKE).or(moments.first()).map(|m| m.time()).unwrap_or_default();
+
+        let mut steps: Vec<TimelineStep> =
+            moments.iter().map(|m| TimelineStep { name: m.name().to_string(), offset: m.time() - made, elapsed: m.elapsed() }).collect();
+        steps.sort_by_key(|s| s.offset);
+
+        let startup = moments.iter().rev().find(|m| m.name() == MOMENT_FIRE).and_then(|m| m.elapsed().map(|e| m.time() + e - made));
+
+        Self { app: app.to_string(), made, startup, steps }
+    }
+
+    /// steps with duration, slowest first
+    pub fn slowest(&self, n: usize) -> Vec<&TimelineStep> {
+        let mut steps: Vec<&TimelineStep> = self.steps.iter().filter(|s| s.elapsed.is_some()).collect();
+        steps.sort_by_key(|s| std::cmp::Reverse(s.elapsed));
+        steps.truncate(n);
+        steps
+    }
+
+    /// human readable report, one step a line
+    pub fn summary(&self) -> String {
+        let mut summary = match self.startup {
+            Some(startup) => format!("rings application:{} startup timeline, total {:.3}ms", self.app, millis(startup)),
+            None => format!("rings application:{} timeline, not fired", self.app),
+        };
+
+        for step in &self.steps {
+            summary.push_str(&format!("\n  +{:>10.3}ms  {}", millis(step.offset), step.name));
+            if let Some(elapsed) = step.elapsed {
+                summary.push_str(&format!("  took {:.3}ms", millis(elapsed)));
+            }
+        }
+
+        let slowest: Vec<String> = self.slowest(3).iter().map(|s| format!("{} {:.3}ms", s.name, millis(s.elapsed.unwrap_or_default()))).collect();
+        if !slowest.is_empty() {
+            summary.push_str(&format!("\n  slowest: {}", slowest.join(", ")));
+        }
+
+        summary
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_timeline_from_moments() {
+        let moments = vec![
+            Moment::of("make", 1_000, None),
+            Moment::of("mod [db] initialize", 1_500, Some(1_000)),
+            Moment::of("fire", 3_000, Some(12_000)),
+            Moment::of("mod [web] fire", 3_000, Some(7_000)),
+            Moment::of("mod [cache] fire", 3_000, Some(4_000)),
+        ];
+
+        let timeline = Timeline::from_moments("test", &moments);
+        assert_eq!(timeline.made, 1_000);
+        assert_eq!(timeline.startup, Some(14_000));
+        assert_eq!(timeline.steps[1], TimelineStep { name: "mod [db] initialize".to_string(), offset: 500, elapsed: Some(1_000) });
+
+        let slowest: Vec<&str> = timeline.slowest(2).iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(slowest, vec!["fire", "mod [web] fire"]);
+        assert!(timeline.summary().contains("total 14.000ms"));
+
+        let json = serde_json::to_string(&timeline).unwrap();
+        assert!(json.contains(r#""startup":14000"#));
+    }
+}
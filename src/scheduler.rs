@@ -1,25 +1,31 @@
 use crate::erx::{simple_conv_boxed, Erx, ResultBoxedE, ResultBoxedEX};
-use crate::rings::RingState;
+use crate::rings::supervise::spawn_supervised;
+use crate::rings::{RingState, SafeRingState};
 use crate::service::ServiceManager;
 use async_trait::async_trait;
 use std::str::FromStr;
 use std::sync::Arc;
 use tokio::sync::RwLock;
-use tokio_cron_scheduler::JobScheduler;
+use tokio_cron_scheduler::{Job, JobScheduler};
 use tracing::{error, info, warn};
 use uuid::Uuid;
 
 /// scheduler manager
 ///
 pub struct SchedulerManager {
-    stage: Arc<RwLock<RingState>>,
+    stage: SafeRingState,
     count: u64,
     scheduler: Arc<RwLock<JobScheduler>>,
+    services: &'static ServiceManager,
+    running: Option<tokio::task::AbortHandle>,
+    /// every scheduled job, kept to re-add them when resumed from paused
+    jobs: Arc<RwLock<Vec<Job>>>,
 }
 
 impl SchedulerManager {
     /// create a new scheduler manager
-    pub(crate) async fn new() -> Self {
+    /// jobs are loaded from the services of the given service manager
+    pub(crate) async fn new(services: &'static ServiceManager) -> Self {
         let mut scheduler = JobScheduler::new().await.unwrap();
         scheduler.set_shutdown_handler(Box::new(|| {
             Box::pin(async move {
@@ -27,19 +33,39 @@ impl SchedulerManager {
             })
         }));
 
-        Self { stage: Arc::new(RwLock::new(RingState::Init)), count: 0, scheduler: Arc::new(RwLock::new(scheduler)) }
+        Self {
+            stage: RingState::inited_safe_ring_state(),
+            count: 0,
+            scheduler: Arc::new(RwLock::new(scheduler)),
+            services,
+            running: None,
+            jobs: Default::default(),
+        }
     }
 
+    /// add job, while paused the job is kept and scheduled when resumed
     pub async fn add_job(&mut self, job: tokio_cron_scheduler::Job) -> ResultBoxedE<String> {
+        if self.stage.get() == RingState::Paused {
+            self.jobs.write().await.push(job.clone());
+            return Ok(job.guid().to_string());
+        }
+
         let scheduler = Arc::clone(&self.scheduler);
         let guard = scheduler.try_write().map_err(simple_conv_boxed)?;
-        guard.add(job).await.map(Into::into).map_err(simple_conv_boxed)
+        let guid = guard.add(job.clone()).await.map_err(simple_conv_boxed)?;
+        self.jobs.write().await.push(job);
+        Ok(guid.into())
     }
 
     pub async fn remove_job(&mut self, job_id: String) -> ResultBoxedEX {
+        let uuid = Uuid::from_str(&job_id).map_err(simple_conv_boxed)?;
+        self.jobs.write().await.retain(|job| job.guid() != uuid);
+        if self.stage.get() == RingState::Paused {
+            return Ok(());
+        }
+
         let scheduler = Arc::clone(&self.scheduler);
         let guard = scheduler.try_write().map_err(simple_conv_boxed)?;
-        let uuid = Uuid::from_str(&job_id).map_err(simple_conv_boxed)?;
         guard.remove(&uuid).await.map_err(simple_conv_boxed)
     }
 }
@@ -78,7 +104,7 @@ impl crate::rings::RingsMod for SchedulerManager {
 
     async fn initialize(&mut self) -> ResultBoxedEX {
         let mut futures = vec![]; // Vec<Box<dyn Future<Output = ()> + Send>>;
-        let srv_manager = ServiceManager::shared().await;
+        let srv_manager = self.services;
 
         let managed: Vec<crate::service::Managed> = srv_manager.managed_services();
         let scheduler = self.scheduler.clone(); // Arc<ToKioRwLock<JobScheduler>>;
@@ -91,11 +117,13 @@ impl crate::rings::RingsMod for SchedulerManager {
                         let job_id = job.guid().to_string();
 
                         let scheduler = Arc::clone(&scheduler);
+                        let jobs = Arc::clone(&self.jobs);
                         futures.push(async move {
                             let scheduler = Arc::clone(&scheduler);
                             let scher = scheduler.write().await;
-                            match scher.add(job).await {
+                            match scher.add(job.clone()).await {
                                 Ok(_) => {
+                                    jobs.write().await.push(job);
                                     info!("Add schedule job[{}] from service[{}] SUCCESS", job_id, service_name);
                                 },
                                 Err(e) => {
@@ -125,7 +153,7 @@ impl crate::rings::RingsMod for SchedulerManager {
 
     async fn shutdown(&mut self) -> ResultBoxedEX {
         info!("scheduler manager [{}] shutdown", SCHEDULER_MANAGER_NAME);
-        let current = *self.stage.try_read().map_err(simple_conv_boxed)?;
+        let current = self.stage.get();
         if !current.is_ready_to_terminating() {
             let current: &str = current.into();
             return Err(Erx::boxed(&format!("Ring:{} current state:{} can not terminate", self.name(), current)));
@@ -136,38 +164,19 @@ impl crate::rings::RingsMod for SchedulerManager {
             error!("scheduler service lock poisoned: {}", ex);
         }
 
-        *self.stage.try_write().map_err(simple_conv_boxed)? = RingState::Terminating;
+        self.stage.set(RingState::Terminating);
 
         Ok(())
     }
 
     async fn fire(&mut self) -> ResultBoxedEX {
-        *self.stage.write().await = RingState::Working;
+        self.stage.set(RingState::Working);
 
         let stage = self.stage.clone();
         let dog = async move {
             info!("scheduler manager [{}] fire, dog watch it....", SCHEDULER_MANAGER_NAME);
-            let duration = tokio::time::Duration::from_millis(100); // 100ms
-            let mut stage_read_lock_failures: i64 = 0;
-            loop {
-                match stage.try_read() {
-                    Ok(stage) => {
-                        let stage = *stage;
-                        if stage == RingState::Terminating || stage == RingState::Terminated {
-                            break;
-                        }
-                    },
-                    Err(ex) => {
-                        warn!("scheduler stage lock poisoned: {}", ex);
-                        stage_read_lock_failures += 1;
-                    },
-                }
-                tokio::time::sleep(duration).await;
-            }
-
-            *stage.write().await = RingState::Terminated;
-
-            stage_read_lock_failures
+            stage.wait_terminating().await;
+            stage.set(RingState::Terminated);
         };
 
         let scheduler = self.scheduler.clone();
@@ -177,23 +186,75 @@ impl crate::rings::RingsMod for SchedulerManager {
             match scher.start().await {
                 Ok(_) => {
                     info!("scheduler start success");
+                    Ok(())
                 },
                 Err(err) => {
                     error!("scheduler failed to start: {}", err);
+                    Err(Erx::boxed(&format!("scheduler failed to start: {}", err)))
                 },
             }
         };
 
-        tokio::spawn(async {
-            run.await;
+        let running = spawn_supervised(SCHEDULER_MANAGER_NAME, &self.stage, async {
+            run.await?;
             dog.await;
+            Ok(())
         });
+        self.running = Some(running);
 
         Ok(())
     }
 
+    /// pause by removing every job from the scheduler, jobs are kept and re-added when resumed
+    async fn pause(&mut self) -> ResultBoxedEX {
+        self.stage.compare_and_set(RingState::Working, RingState::Paused).map_err(|current| {
+            let current: &str = current.into();
+            Erx::boxed(&format!("Ring:{} current state:{} can not pause", SCHEDULER_MANAGER_NAME, current))
+        })?;
+
+        let scheduler = self.scheduler.read().await;
+        for job in self.jobs.read().await.iter() {
+            if let Err(ex) = scheduler.remove(&job.guid()).await {
+                warn!("scheduler pause, remove job[{}] failed: {}", job.guid(), ex);
+            }
+        }
+
+        info!("scheduler manager [{}] paused", SCHEDULER_MANAGER_NAME);
+        Ok(())
+    }
+
+    async fn resume(&mut self) -> ResultBoxedEX {
+        self.stage.compare_and_set(RingState::Paused, RingState::Working).map_err(|current| {
+            let current: &str = current.into();
+            Erx::boxed(&format!("Ring:{} current state:{} can not resume", SCHEDULER_MANAGER_NAME, current))
+        })?;
+
+        let scheduler = self.scheduler.read().await;
+        for job in self.jobs.read().await.iter() {
+            if let Err(ex) = scheduler.add(job.clone()).await {
+                error!("scheduler resume, add job[{}] failed: {}", job.guid(), ex);
+            }
+        }
+
+        info!("scheduler manager [{}] resumed", SCHEDULER_MANAGER_NAME);
+        Ok(())
+    }
+
+    async fn force_stop(&mut self) -> ResultBoxedEX {
+        if let Some(running) = self.running.take() {
+            running.abort();
+        }
+
+        self.stage.set(RingState::Terminated);
+        Ok(())
+    }
+
     async fn stage(&self) -> RingState {
-        *self.stage.read().await
+        self.stage.get()
+    }
+
+    fn stage_watch(&self) -> Option<tokio::sync::watch::Receiver<RingState>> {
+        Some(self.stage.subscribe())
     }
 
     fn level(&self) -> i64 {
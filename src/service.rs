@@ -1,6 +1,5 @@
 use crate::erx::{simple_conv_boxed, Erx, ResultBoxedE, ResultBoxedEX};
 use std::sync::{Arc, RwLock};
-use tokio::sync::OnceCell;
 use tokio_cron_scheduler::Job;
 
 /// let shared = rings::service::ServiceManager::shared().await;
@@ -59,18 +58,12 @@ macro_rules! with_service_write {
     }};
 }
 
-static SHARED_MANAGER: OnceCell<ServiceManager> = OnceCell::const_new();
+/// service managers, one per rings application, living as long as the process
+static MANAGERS: std::sync::RwLock<Vec<&'static ServiceManager>> = std::sync::RwLock::new(Vec::new());
 
-static SHARED_SERVICE_NAME: &str = "SharedServiceManager";
-
-/// shared service manager init
+/// shared service manager, the one owned by the default rings application
 pub(crate) async fn shared_service_manager() -> &'static ServiceManager {
-    SHARED_MANAGER
-        .get_or_init(|| async {
-            tracing::info!("Initializing shared service manager");
-            ServiceManager::new(SHARED_SERVICE_NAME)
-        })
-        .await
+    ServiceManager::make(crate::rings::default_rings_name())
 }
 
 /// registe to shared service manager
@@ -82,6 +75,20 @@ pub async fn registe_to_shared<T: ServiceTrait + Default>() {
     shared_service_manager.register::<T>().expect("registration failed");
 }
 
+/// registe to the service manager of the named rings application, the application must be made
+/// # Arguments
+/// * `app` - rings application name
+pub async fn registe_to<T: ServiceTrait + Default>(app: &str) {
+    let manager = ServiceManager::of(app).unwrap_or_else(|| panic!("rings application {} not made", app));
+    manager.register::<T>().expect("registration failed");
+}
+
+/// registe to the service manager of the rings application being built, the one last made by `R::make`,
+/// the default one before any is made
+pub async fn registe_to_building<T: ServiceTrait + Default>() {
+    ServiceManager::make(&crate::rings::building_rings_name()).register::<T>().expect("registration failed");
+}
+
 /// Service Trait
 /// # Methods
 /// * `name` - get service name
@@ -152,6 +159,7 @@ pub struct ServiceManager {
 /// * `unregister` - unregister service
 /// * `get` - get managed service
 /// * `shared` - get shared service manager
+/// * `of` - get service manager of a rings application
 impl ServiceManager {
     /// make new service manager
     /// # Arguments
@@ -314,10 +322,42 @@ impl ServiceManager {
 
     /// get shared service manager
     /// # Returns
-    /// * `&'static ServiceManager` - shared service manager
+    /// * `&'static ServiceManager` - service manager of the default rings application
     pub async fn shared() -> &'static ServiceManager {
         shared_service_manager().await
     }
+
+    /// get the service manager of the named rings application
+    /// # Arguments
+    /// * `app` - rings application name
+    /// # Returns
+    /// * `Some(&'static ServiceManager)` - service manager
+    /// * `None` - no rings application made with the name
+    pub fn of(app: &str) -> Option<&'static ServiceManager> {
+        MANAGERS.read().expect("service managers lock poisoned").iter().find(|m| m.name.eq(app)).copied()
+    }
+
+    /// get the service manager of the named rings application, make it if not exists,
+    /// only applications being made or built own one, see `R::make` and `registe_to_building`
+    /// # Arguments
+    /// * `app` - rings application name
+    /// # Returns
+    /// * `&'static ServiceManager` - service manager
+    pub(crate) fn make(app: &str) -> &'static ServiceManager {
+        if let Some(found) = Self::of(app) {
+            return found;
+        }
+
+        let mut managers = MANAGERS.write().expect("service managers lock poisoned");
+        if let Some(found) = managers.iter().find(|m| m.name.eq(app)) {
+            return found;
+        }
+
+        tracing::info!("Initializing service manager for rings application: {}", app);
+        let manager: &'static ServiceManager = Box::leak(Box::new(ServiceManager::new(app)));
+        managers.push(manager);
+        manager
+    }
 }
 
 #[macro_export]
@@ -358,6 +398,18 @@ mod tests {
         println!("==={:#?}", r);
     }
 
+    #[tokio::test]
+    async fn test_service_manager_per_app() {
+        let admin = ServiceManager::make("test_admin_app");
+        let public = ServiceManager::make("test_public_app");
+        admin.register::<TestService>().unwrap();
+
+        assert!(admin.get::<TestService>().is_some());
+        assert!(public.get::<TestService>().is_none());
+        assert!(std::ptr::eq(admin, ServiceManager::of("test_admin_app").unwrap()));
+        assert!(ServiceManager::of("test_admn_app").is_none());
+    }
+
     struct TestService {}
 
     impl Default for TestService {
@@ -18,6 +18,7 @@ pub mod url;
 pub mod validation;
 
 use crate::erx::ResultBoxedE;
+use crate::rings::supervise::{spawn_supervised, RestartPolicy};
 use crate::rings::{RingState, RingsMod, SafeRingState};
 use crate::web::luaction::LuaAction;
 
@@ -53,8 +54,14 @@ pub struct Web {
     router_maker: fn() -> Vec<Router>,
     router_reconfiger: Option<fn(router: Router) -> Router>,
     middleware_manager: Arc<crate::web::middleware::Manager>,
+    serving: Option<tokio::task::AbortHandle>,
+    paused_retry_after: u64,
+    restart_policy: RestartPolicy,
 }
 
+/// default `Retry-After` seconds answered while web paused
+pub const PAUSED_RETRY_AFTER: u64 = 30;
+
 pub fn make_web(
     name: &str, bind: &str, router_maker: fn() -> Vec<Router>, middlewares: Vec<Box<dyn crate::web::middleware::Middleware>>,
 ) -> Web {
@@ -67,9 +74,39 @@ pub fn make_web(
         router_maker,
         middleware_manager: Arc::new(crate::web::middleware::Manager::new(middlewares)),
         router_reconfiger: None,
+        serving: None,
+        paused_retry_after: PAUSED_RETRY_AFTER,
+        restart_policy: RestartPolicy::Never,
     }
 }
 
+/// paths served while web paused, the health probes report the pause themselves
+fn serves_paused(path: &str) -> bool {
+    path == crate::health::HEALTHZ_PATH || path == crate::health::READYZ_PATH
+}
+
+/// answer 503 with `Retry-After` while web paused, the listener stays open
+async fn paused_guard(
+    axum::extract::State((stage, retry_after)): axum::extract::State<(SafeRingState, u64)>, request: axum::extract::Request,
+    next: axum::middleware::Next,
+) -> axum::response::Response {
+    if stage.get() != RingState::Paused || serves_paused(request.uri().path()) {
+        return next.run(request).await;
+    }
+
+    axum::response::Response::builder()
+        .status(axum::http::StatusCode::SERVICE_UNAVAILABLE)
+        .header(axum::http::header::RETRY_AFTER, retry_after.to_string())
+        .header(axum::http::header::CONTENT_TYPE, "application/json")
+        .body(axum::body::Body::from(r#"{"error": "service paused"}"#))
+        .unwrap_or_default()
+}
+
+/// the router guarded by `paused_guard`
+fn pause_guarded(router: Router, stage: SafeRingState, retry_after: u64) -> Router {
+    router.layer(axum::middleware::from_fn_with_state((stage, retry_after), paused_guard))
+}
+
 impl crate::conf::Web {
     pub fn bind_addr(&self) -> String {
         let bind = self.bind.clone().unwrap_or("0.0.0.0".to_string());
@@ -115,6 +152,18 @@ impl Web {
         self
     }
 
+    /// set `Retry-After` seconds answered while web paused
+    pub fn set_paused_retry_after(&mut self, seconds: u64) -> &mut Self {
+        self.paused_retry_after = seconds;
+        self
+    }
+
+    /// set restart policy applied when serving fails after fired
+    pub fn set_restart_policy(&mut self, policy: RestartPolicy) -> &mut Self {
+        self.restart_policy = policy;
+        self
+    }
+
     pub fn middleware_manager(&mut self) -> Arc<crate::web::middleware::Manager> {
         Arc::clone(&self.middleware_manager)
     }
@@ -186,44 +235,81 @@ impl RingsMod for Web {
     // }
 
     async fn fire(&mut self) -> ResultBoxedE<()> {
-        let web_listen = |name: String, bind: String, router: Router, stage: Arc<tokio::sync::RwLock<RingState>>| async move {
-            let listen = tokio::net::TcpListener::bind(bind.as_str()).await;
-            if listen.is_err() {
-                error!("[{} - webserver] can't bind to : {}  ERROR: {}", &name, bind, listen.unwrap_err());
-                return;
-            }
-
-            let graceful = |stage: Arc<tokio::sync::RwLock<RingState>>, name: String| async move {
-                loop {
-                    let stage = *stage.read().await;
-                    if stage == RingState::Terminating || stage == RingState::Terminated {
-                        info!("WebMod[ {} ] terminating, current state:{:?}", name, stage);
-                        break;
-                    }
-                    tokio::time::sleep(std::time::Duration::from_millis(500)).await;
-                }
+        let web_listen = |name: String, bind: String, listen: tokio::net::TcpListener, router: Router, stage: SafeRingState| async move {
+            let graceful = |stage: SafeRingState, name: String| async move {
+                let stage = stage.wait_terminating().await;
+                info!("WebMod[ {} ] terminating, current state:{:?}", name, stage);
             };
 
-            let serve = axum::serve(listen.unwrap(), router).with_graceful_shutdown(graceful(Arc::clone(&stage), name.clone()));
+            let serve = axum::serve(listen, router).with_graceful_shutdown(graceful(stage.clone(), name.clone()));
 
             info!("WebMod[ {} ] try served : {}", &name, bind);
             // serve.await.expect(format!("WebMod[ {} ] failed to served : {}", &name, bind).as_str());
 
-            serve.await.unwrap_or_else(|_| panic!("WebMod[ {} ] failed to served : {}", &name, bind));
-
-            // *stage.write().unwrap() = RingState::Terminated;
-            let _ = RingState::safe_ring_state_must_set(&stage, RingState::Terminated).await;
+            serve.await.map_err(|ex| {
+                error!("WebMod[ {} ] failed to served : {} ERROR: {}", &name, bind, ex);
+                crate::erx::Erx::boxed(&format!("WebMod[ {} ] failed to served : {} ERROR: {}", &name, bind, ex))
+            })
         };
 
-        let integrated_router = crate::web::middleware::Manager::integrated(self.middleware_manager.clone(), self.router.clone());
+        // bind before spawning, so a bind failure fails the fire instead of a silent log
+        let listen = tokio::net::TcpListener::bind(self.bind.as_str()).await.map_err(|ex| {
+            error!("[{} - webserver] can't bind to : {}  ERROR: {}", &self.name, &self.bind, ex);
+            crate::erx::Erx::boxed(&format!("WebMod[ {} ] can't bind to : {} ERROR: {}", &self.name, &self.bind, ex))
+        })?;
+
+        let integrated_router = pause_guarded(
+            crate::web::middleware::Manager::integrated(self.middleware_manager.clone(), self.router.clone()),
+            self.stage.clone(),
+            self.paused_retry_after,
+        );
+
+        RingState::safe_ring_state_must_set(&self.stage, RingState::Working).await?;
 
-        tokio::spawn(web_listen(self.name.clone(), self.bind.clone(), integrated_router, Arc::clone(&self.stage)));
+        let serving = web_listen(self.name.clone(), self.bind.clone(), listen, integrated_router, self.stage.clone());
+        self.serving = Some(spawn_supervised(&self.name, &self.stage, serving));
 
         Ok(())
     }
 
+    async fn pause(&mut self) -> ResultBoxedE<()> {
+        self.stage.compare_and_set(RingState::Working, RingState::Paused).map_err(|current| {
+            crate::erx::Erx::boxed(&format!(
+                "Ring:{} current state:{} can not pause",
+                self.name,
+                <RingState as Into<&str>>::into(current)
+            ))
+        })
+    }
+
+    async fn resume(&mut self) -> ResultBoxedE<()> {
+        self.stage.compare_and_set(RingState::Paused, RingState::Working).map_err(|current| {
+            crate::erx::Erx::boxed(&format!(
+                "Ring:{} current state:{} can not resume",
+                self.name,
+                <RingState as Into<&str>>::into(current)
+            ))
+        })
+    }
+
+    async fn force_stop(&mut self) -> ResultBoxedE<()> {
+        if let Some(serving) = self.serving.take() {
+            serving.abort();
+        }
+
+        RingState::safe_ring_state_must_set(&self.stage, RingState::Terminated).await
+    }
+
     async fn stage(&self) -> RingState {
-        RingState::safe_ring_state_must_get(&self.stage).await.unwrap()
+        self.stage.get()
+    }
+
+    fn stage_watch(&self) -> Option<tokio::sync::watch::Receiver<RingState>> {
+        Some(self.stage.subscribe())
+    }
+
+    fn restart_policy(&self) -> RestartPolicy {
+        self.restart_policy.clone()
     }
 
     fn level(&self) -> i64 {
@@ -242,3 +328,30 @@ impl RingsMod for Web {
 // }
 
 crate::impl_any_trait!(Web);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tower::ServiceExt;
+
+    async fn status_of(router: &Router, path: &str) -> axum::http::StatusCode {
+        let request = axum::http::Request::builder().uri(path).body(axum::body::Body::empty()).unwrap();
+        router.clone().oneshot(request).await.unwrap().status()
+    }
+
+    #[tokio::test]
+    async fn test_health_while_paused() {
+        let stage = RingState::inited_safe_ring_state();
+        stage.set(RingState::Paused);
+        let routes = Router::new()
+            .route(crate::health::HEALTHZ_PATH, axum::routing::get(|| async { "alive" }))
+            .route("/orders", axum::routing::get(|| async { "orders" }));
+        let router = pause_guarded(routes, stage.clone(), PAUSED_RETRY_AFTER);
+
+        assert_eq!(status_of(&router, crate::health::HEALTHZ_PATH).await, axum::http::StatusCode::OK);
+        assert_eq!(status_of(&router, "/orders").await, axum::http::StatusCode::SERVICE_UNAVAILABLE);
+
+        stage.set(RingState::Working);
+        assert_eq!(status_of(&router, "/orders").await, axum::http::StatusCode::OK);
+    }
+}
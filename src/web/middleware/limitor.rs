@@ -264,6 +264,7 @@
 //! ```
 
 use crate::erx::{Erx, Layouted};
+use crate::model::facade::redis::namespace;
 use crate::web::api::Out;
 use crate::web::middleware::{ApplyKind, Context, Middleware, MiddlewareEventErr, MiddlewareFuture, MiddlewareImpl, Pattern};
 use crate::web::middleware::Parts;
@@ -452,18 +453,25 @@ pub struct LimitorConfig {
     /// How long to block clients when they exceed rate limits.
     /// 当客户端超过限流时阻塞的时长。
     pub block_duration: Duration,
+
+    /// Redis key namespace / Redis 键命名空间
+    ///
+    /// Keys are namespaced by the application namespace when `None`, not namespaced when empty.
+    /// 为 `None` 时使用应用命名空间，为空字符串时不加命名空间。
+    pub namespace: Option<String>,
 }
 
 impl std::fmt::Debug for LimitorConfig {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("LimitorConfig")
             .field("priority", &self.priority)
-            .field("redis_url", &self.redis_url)
+            .field("redis_url", &crate::conf::secret::redact(&self.redis_url))
             .field("rules", &self.rules)
             .field("apply", &self.apply.as_ref().map(|_| "Some(Fn)"))
             .field("default_limit", &self.default_limit)
             .field("key_extractor", &self.key_extractor.as_ref().map(|_| "Some(Fn)"))
             .field("block_duration", &self.block_duration)
+            .field("namespace", &self.namespace)
             .finish()
     }
 }
@@ -478,6 +486,7 @@ impl LimitorConfig {
             default_limit: Some((100, Duration::from_secs(60))),
             key_extractor: None,
             block_duration: Duration::from_secs(300),
+            namespace: None,
         }
     }
 
@@ -517,6 +526,11 @@ impl LimitorConfig {
         self
     }
 
+    pub fn namespace(mut self, namespace: String) -> Self {
+        self.namespace = Some(namespace);
+        self
+    }
+
     pub fn validate(&self) -> Result<(), Box<Error>> {
         if self.rules.is_empty() && self.default_limit.is_none() {
             return Err(Box::new(Error::ConfigError("At least one rule or default limit is required".to_string())));
@@ -688,7 +702,7 @@ impl Limitor {
         config.validate()?;
 
         let redis_client = redis::Client::open(config.redis_url.as_str())
-            .map_err(|err| Box::new(Error::ConfigError(format!("Invalid Redis URL '{}': {}", config.redis_url, err))))?;
+            .map_err(|err| Box::new(Error::ConfigError(format!("Invalid Redis URL '{}': {}", crate::conf::secret::redact(&config.redis_url), err))))?;
 
         // Token Bucket Algorithm Implementation / 令牌桶算法实现
         //
@@ -697,6 +711,7 @@ impl Limitor {
         //
         // Arguments / 参数:
         // - KEYS[1]: Rate limiting key / 限流键
+        // - KEYS[2]: Block key / 阻塞键
         // - ARGV[1]: Bucket capacity / 桶容量
         // - ARGV[2]: Token refill rate per minute / 每分钟令牌补充速率
         // - ARGV[3]: Current timestamp / 当前时间戳
@@ -711,7 +726,7 @@ impl Limitor {
             local capacity = tonumber(ARGV[1])     -- Maximum tokens / 最大令牌数
             local refill_rate = tonumber(ARGV[2]) -- Tokens per minute / 每分钟令牌数
             local current_time = tonumber(ARGV[3]) -- Current timestamp / 当前时间戳
-            local block_key = "block:" .. key      -- Block key / 阻塞键
+            local block_key = KEYS[2]              -- Block key / 阻塞键
             local block_duration = tonumber(ARGV[4]) -- Block duration / 阻塞时长
 
             -- Get current token count or initialize to capacity / 获取当前令牌数或初始化为容量
@@ -748,6 +763,7 @@ impl Limitor {
         //
         // 参数 / Parameters:
         // KEYS[1]: 限流键 / Rate limiting key
+        // KEYS[2]: 封禁键 / Block key
         // ARGV[1]: 窗口截止时间戳 / Window cutoff timestamp
         // ARGV[2]: 窗口容量 / Window capacity
         // ARGV[3]: 当前时间戳 / Current timestamp
@@ -762,7 +778,7 @@ impl Limitor {
             local cutoff = tonumber(ARGV[1])
             local capacity = tonumber(ARGV[2])
             local current_time = tonumber(ARGV[3])
-            local block_key = "block:" .. key
+            local block_key = KEYS[2]
             local block_duration = tonumber(ARGV[4])
 
             -- 清理窗口外的过期请求 / Remove expired requests outside the window
@@ -995,7 +1011,11 @@ impl Limitor {
         let mut redis_conn = self.redis_client.get_multiplexed_tokio_connection().await.map_err(|e| Box::new(Error::from(e)))?;
 
         // 检查是否被封禁 / Check if blocked
-        let block_key = format!("block:{}", key);
+        let key_namespace = match &self.config.namespace {
+            Some(namespace) => namespace.clone(),
+            None => namespace::app_namespace().await,
+        };
+        let block_key = namespace::namespaced(&key_namespace, &format!("block:{}", key));
         let blocked: Option<i64> = redis_conn.get(&block_key).await.map_err(|e| Box::new(Error::from(e)))?;
 
         if let Some(block_until) = blocked {
@@ -1006,13 +1026,13 @@ impl Limitor {
         }
 
         // 根据策略执行限流检查 / Execute rate limiting check based on strategy
-        let limit_key = format!("limit:{}", key);
+        let limit_key = namespace::namespaced(&key_namespace, &format!("limit:{}", key));
         let current_time = chrono::Utc::now().timestamp() as u64;
 
         match rule.strategy {
-            LimitStrategy::TokenBucket => self.check_token_bucket(&mut redis_conn, &limit_key, rule, current_time).await,
-            LimitStrategy::FixedWindow => self.check_fixed_window(&mut redis_conn, &limit_key, rule, current_time).await,
-            LimitStrategy::SlidingWindow => self.check_sliding_window(&mut redis_conn, &limit_key, rule, current_time).await,
+            LimitStrategy::TokenBucket => self.check_token_bucket(&mut redis_conn, &limit_key, &block_key, rule, current_time).await,
+            LimitStrategy::FixedWindow => self.check_fixed_window(&mut redis_conn, &limit_key, &block_key, rule, current_time).await,
+            LimitStrategy::SlidingWindow => self.check_sliding_window(&mut redis_conn, &limit_key, &block_key, rule, current_time).await,
         }
     }
 
@@ -1024,6 +1044,7 @@ impl Limitor {
     /// # 参数 / Parameters
     /// * `redis_conn` - Redis连接 / Redis connection
     /// * `key` - 限流键 / Rate limiting key
+    /// * `block_key` - 封禁键 / Block key
     /// * `rule` - 限流规则 / Rate limiting rule
     /// * `current_time` - 当前时间戳 / Current timestamp
     ///
@@ -1031,11 +1052,12 @@ impl Limitor {
     /// * `Result<(), Box<Error>>` - 成功时允许访问，失败时返回限流错误
     ///   Success allows access, failure returns rate limiting error
     async fn check_token_bucket(
-        &self, redis_conn: &mut redis::aio::MultiplexedConnection, key: &str, rule: &LimitRule, current_time: u64,
+        &self, redis_conn: &mut redis::aio::MultiplexedConnection, key: &str, block_key: &str, rule: &LimitRule, current_time: u64,
     ) -> Result<(), Box<Error>> {
         // 执行Lua脚本检查令牌桶 / Execute Lua script to check token bucket
         let result: Vec<i64> = self.token_bucket_script
             .key(key)
+            .key(block_key)
             .arg(rule.capacity)
             .arg(rule.refill_rate)
             .arg(current_time)
@@ -1065,6 +1087,7 @@ impl Limitor {
     /// # 参数 / Parameters
     /// * `redis_conn` - Redis连接 / Redis connection
     /// * `key` - 限流键 / Rate limiting key
+    /// * `block_key` - 封禁键 / Block key
     /// * `rule` - 限流规则 / Rate limiting rule
     /// * `current_time` - 当前时间戳 / Current timestamp
     ///
@@ -1072,7 +1095,7 @@ impl Limitor {
     /// * `Result<(), Box<Error>>` - 成功时允许访问，失败时返回限流错误
     ///   Success allows access, failure returns rate limiting error
     async fn check_fixed_window(
-        &self, redis_conn: &mut redis::aio::MultiplexedConnection, key: &str, rule: &LimitRule, current_time: u64,
+        &self, redis_conn: &mut redis::aio::MultiplexedConnection, key: &str, block_key: &str, rule: &LimitRule, current_time: u64,
     ) -> Result<(), Box<Error>> {
         let window_size = rule.window_size.as_secs();
         // 生成当前窗口的键 / Generate key for current window
@@ -1089,9 +1112,8 @@ impl Limitor {
         // 检查是否超过限制 / Check if limit is exceeded
         if count as u64 > rule.capacity {
             // 设置封禁 / Set block
-            let block_key = format!("block:{}", key);
-            redis_conn.set::<_, _, ()>(&block_key, (current_time + self.config.block_duration.as_secs()) as i64).await.map_err(|e| Box::new(Error::from(e)))?;
-            redis_conn.expire::<_, ()>(&block_key, self.config.block_duration.as_secs() as i64).await.map_err(|e| Box::new(Error::from(e)))?;
+            redis_conn.set::<_, _, ()>(block_key, (current_time + self.config.block_duration.as_secs()) as i64).await.map_err(|e| Box::new(Error::from(e)))?;
+            redis_conn.expire::<_, ()>(block_key, self.config.block_duration.as_secs() as i64).await.map_err(|e| Box::new(Error::from(e)))?;
 
             Err(Box::new(Error::LimitExceeded {
                 key: key.to_string(),
@@ -1112,6 +1134,7 @@ impl Limitor {
     /// # 参数 / Parameters
     /// * `redis_conn` - Redis连接 / Redis connection
     /// * `key` - 限流键 / Rate limiting key
+    /// * `block_key` - 封禁键 / Block key
     /// * `rule` - 限流规则 / Rate limiting rule
     /// * `current_time` - 当前时间戳 / Current timestamp
     ///
@@ -1119,7 +1142,7 @@ impl Limitor {
     /// * `Result<(), Box<Error>>` - 成功时允许访问，失败时返回限流错误
     ///   Success allows access, failure returns rate limiting error
     async fn check_sliding_window(
-        &self, redis_conn: &mut redis::aio::MultiplexedConnection, key: &str, rule: &LimitRule, current_time: u64,
+        &self, redis_conn: &mut redis::aio::MultiplexedConnection, key: &str, block_key: &str, rule: &LimitRule, current_time: u64,
     ) -> Result<(), Box<Error>> {
         let window_size = rule.window_size.as_secs();
         // 计算窗口截止时间 / Calculate window cutoff time
@@ -1128,6 +1151,7 @@ impl Limitor {
         // 执行Lua脚本检查滑动窗口 / Execute Lua script to check sliding window
         let result: Vec<i64> = self.sliding_window_script
             .key(key)
+            .key(block_key)
             .arg(cutoff)
             .arg(rule.capacity)
             .arg(current_time)
@@ -1,4 +1,5 @@
 use crate::erx::{Erx, Layouted};
+use crate::model::facade::redis::namespace;
 use crate::tools::hash;
 use crate::web::api::Out;
 use crate::web::middleware::{ApplyKind, Context, Middleware, MiddlewareEventErr, MiddlewareFuture, MiddlewareImpl, Pattern};
@@ -67,6 +68,8 @@ pub struct SignatorConfig {
     pub nonce_lifetime: i64,
     /// 后门，开发时候方便用
     pub backdoor: Option<String>,
+    /// Redis 键命名空间，未设置时使用应用命名空间，空字符串表示不加命名空间
+    pub namespace: Option<String>,
 }
 
 impl std::fmt::Debug for SignatorConfig {
@@ -75,12 +78,13 @@ impl std::fmt::Debug for SignatorConfig {
             .field("debug_level", &self.debug_level.to_string())
             .field("priority", &self.priority)
             .field("key_loader", &"KeyLoader")
-            .field("redis_url", &self.redis_url)
+            .field("redis_url", &crate::conf::secret::redact(&self.redis_url))
             .field("apply", &self.apply.as_ref().map(|_| "Some(Fn)"))
             .field("methods", &self.methods)
             .field("patterns", &self.patterns)
             .field("nonce_lifetime", &self.nonce_lifetime)
-            .field("backdoor", &self.backdoor)
+            .field("backdoor", &self.backdoor.as_ref().map(|_| crate::conf::secret::REDACTED))
+            .field("namespace", &self.namespace)
             .finish()
     }
 }
@@ -97,6 +101,7 @@ impl SignatorConfig {
             patterns: None,
             nonce_lifetime: DEFAULT_NONCE_LIFETIME,
             backdoor: None,
+            namespace: None,
         }
     }
 
@@ -228,6 +233,12 @@ impl SignatorConfig {
         self
     }
 
+    /// 设置 Redis 键命名空间
+    pub fn namespace(mut self, namespace: String) -> Self {
+        self.namespace = Some(namespace);
+        self
+    }
+
     /// 验证配置是否完整和有效
     pub fn validate(&self) -> Result<(), Box<Error>> {
         let boxed_error = |c: &str| Err(Box::new(Error::ConfigError(c.to_string())));
@@ -385,8 +396,8 @@ impl Signator {
         config.validate()?;
 
         let redis_client = redis::Client::open(config.redis_url.as_str()).map_err(|err| {
-            tracing::error!("Failed to create Redis client for URL {}: {}", config.redis_url, err);
-            Box::new(Error::ConfigError(format!("Invalid Redis URL '{}': {}", config.redis_url, err)))
+            tracing::error!("Failed to create Redis client for URL {}: {}", crate::conf::secret::redact(&config.redis_url), err);
+            Box::new(Error::ConfigError(format!("Invalid Redis URL '{}': {}", crate::conf::secret::redact(&config.redis_url), err)))
         })?;
 
         let config = Arc::new(config);
@@ -455,7 +466,7 @@ impl Signator {
     /// 5. Sets expiration on the Redis key to automatically clean up user data
     ///
     /// The nonce validation uses Redis sorted sets where:
-    /// - Key: "XR:{user_id}"
+    /// - Key: "{namespace}:XR:{user_id}", see `model::facade::redis::namespace`
     /// - Member: nonce value
     /// - Score: timestamp when nonce was used
     ///
@@ -463,7 +474,11 @@ impl Signator {
     /// providing protection against replay attacks while automatically cleaning up old data.
     async fn validate_nonce(&self, payload: &Payload) -> Result<(), Box<Error>> {
         let mut redis_conn = self.redis_client.get_multiplexed_tokio_connection().await.map_err(redis_error_to_boxed)?;
-        let redis_key = format!("XR:{}", payload.get_user_id());
+        let key_namespace = match &self.config.namespace {
+            Some(namespace) => namespace.clone(),
+            None => namespace::app_namespace().await,
+        };
+        let redis_key = namespace::namespaced(&key_namespace, &format!("XR:{}", payload.get_user_id()));
         let nonce_value = payload.get_nonce();
 
         let existing_score: Option<i64> = redis_conn.zscore(redis_key.as_str(), &nonce_value).await.map_err(redis_error_to_boxed)?;
@@ -1198,6 +1213,11 @@ mod tests {
         assert!(debug_str.contains("SignatorConfig"));
         assert!(debug_str.contains("priority: 50"));
         assert!(debug_str.contains("Some(Fn)"));
+
+        let config = SignatorConfig::new(config.key_loader.clone(), "redis://:hidden@localhost:6379".to_string()).backdoor("open-sesame".to_string());
+        let debug_str = format!("{:?}", config);
+        assert!(!debug_str.contains("hidden"));
+        assert!(!debug_str.contains("open-sesame"));
     }
 
     #[test]